@@ -1,15 +1,45 @@
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use bevy::utils::HashSet;
 use avian2d::prelude::*;
 use rand::prelude::*;
-use crate::weapons::Bullet;
+use crate::weapons::{Bullet, Faction};
+use crate::bounds::{seam_contacts, BoundingRadius, ScreenWrap, WorldBounds};
+use crate::loot;
 use crate::particles;
+use crate::rng::GameRng;
+use crate::safety::{MovingCircle, SpawnSafety};
+use crate::score::ScoreEvent;
 
-#[derive(Component)]
+#[derive(Component, Debug, Clone, Copy)]
 pub struct Asteroid {
     pub size: AsteroidSize,
+    pub composition: Composition,
+    /// Bullet hits left before the asteroid breaks up
+    pub hit_points: u32,
+    /// Beam damage soaked up towards the next lost hit point, in hit points
+    pub beam_damage: f32,
 }
 
-#[derive(Clone, Copy, Debug)]
+impl Asteroid {
+    /// A fresh, undamaged asteroid.
+    pub fn new(size: AsteroidSize, composition: Composition) -> Self {
+        Self {
+            size,
+            composition,
+            hit_points: composition.hit_points(),
+            beam_damage: 0.0,
+        }
+    }
+
+    /// Points awarded for destroying this asteroid.
+    pub fn points(&self) -> u32 {
+        self.size.points() * self.composition.score_multiplier()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum AsteroidSize {
     Large,
     Medium, 
@@ -17,6 +47,10 @@ pub enum AsteroidSize {
 }
 
 impl AsteroidSize {
+    /// Every size, from largest to smallest
+    pub const ALL: [AsteroidSize; 3] = [AsteroidSize::Large, AsteroidSize::Medium, AsteroidSize::Small];
+
+    /// Mean distance from the asteroid's center to the corners of its outline.
     pub fn radius(self) -> f32 {
         match self {
             AsteroidSize::Large => 40.0,
@@ -24,7 +58,25 @@ impl AsteroidSize {
             AsteroidSize::Small => 15.0,
         }
     }
-    
+
+    /// Mass of an asteroid of this size at the default density of one per square unit.
+    ///
+    /// Measured on a disc of the mean radius, which is close enough to the
+    /// area of its jagged outline.
+    pub fn mass(self) -> f32 {
+        std::f32::consts::PI * self.radius() * self.radius()
+    }
+
+    /// Points awarded for destroying an asteroid of this size.
+    /// Smaller rocks are harder to hit, so they are worth more.
+    pub fn points(self) -> u32 {
+        match self {
+            AsteroidSize::Large => 20,
+            AsteroidSize::Medium => 50,
+            AsteroidSize::Small => 100,
+        }
+    }
+
     pub fn split(self) -> Option<AsteroidSize> {
         match self {
             AsteroidSize::Large => Some(AsteroidSize::Medium),
@@ -34,220 +86,713 @@ impl AsteroidSize {
     }
 }
 
-#[derive(Resource)]
-pub struct AsteroidSpawnTimer(pub Timer);
+/// What an asteroid is made of, which decides how tough it is and how it breaks up.
+///
+/// - Rock takes one hit and splits in two
+/// - Ice takes one hit and shatters straight into a spray of small shards
+/// - Metal takes several hits, splits in two and drops loot
+/// - Crystal takes two hits, splits in three and is worth triple
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Composition {
+    Rock,
+    Ice,
+    Metal,
+    Crystal,
+}
+
+impl Composition {
+    /// Every composition, in the order `CompositionWeights` lists them.
+    pub const ALL: [Composition; 4] = [
+        Composition::Rock,
+        Composition::Ice,
+        Composition::Metal,
+        Composition::Crystal,
+    ];
 
-impl Default for AsteroidSpawnTimer {
-    fn default() -> Self {
-        Self(Timer::from_seconds(3.0, TimerMode::Repeating))
+    /// Bullet hits an asteroid of this composition takes before it breaks up.
+    pub fn hit_points(self) -> u32 {
+        match self {
+            Composition::Rock | Composition::Ice => 1,
+            Composition::Metal => 3,
+            Composition::Crystal => 2,
+        }
+    }
+
+    /// Fill color of the asteroid's mesh.
+    pub fn color(self) -> Color {
+        match self {
+            Composition::Rock => Color::srgb(0.7, 0.7, 0.7),
+            Composition::Ice => Color::srgb(0.7, 0.9, 1.0),
+            Composition::Metal => Color::srgb(0.45, 0.5, 0.55),
+            Composition::Crystal => Color::srgb(0.8, 0.4, 1.0),
+        }
+    }
+
+    /// Number of fragments a destroyed asteroid breaks into, if it is big enough to split.
+    pub fn fragment_count(self) -> usize {
+        match self {
+            Composition::Rock | Composition::Metal => 2,
+            Composition::Ice => 4,
+            Composition::Crystal => 3,
+        }
+    }
+
+    /// Size of the fragments an asteroid of `size` breaks into, or `None` if it just crumbles.
+    pub fn fragment_size(self, size: AsteroidSize) -> Option<AsteroidSize> {
+        match (self, size) {
+            (_, AsteroidSize::Small) => None,
+            // Ice skips a size and shatters straight to shards
+            (Composition::Ice, _) => Some(AsteroidSize::Small),
+            _ => size.split(),
+        }
+    }
+
+    /// Multiplier applied to `AsteroidSize::points` for destroying an asteroid.
+    pub fn score_multiplier(self) -> u32 {
+        match self {
+            Composition::Rock | Composition::Ice => 1,
+            Composition::Metal => 2,
+            Composition::Crystal => 3,
+        }
+    }
+
+    /// Returns true if destroying the asteroid leaves a loot pickup behind.
+    pub fn drops_loot(self) -> bool {
+        self == Composition::Metal
     }
 }
 
-#[derive(Resource)]
-pub struct AsteroidCount {
-    pub max_asteroids: usize,
-    pub current_count: usize,
+/// Relative odds of each composition for the asteroids a wave spawns.
+///
+/// Weights are relative to each other, so `rock: 3, ice: 1` makes one in four
+/// asteroids icy. If every weight is zero, only rock is spawned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompositionWeights {
+    pub rock: u32,
+    pub ice: u32,
+    pub metal: u32,
+    pub crystal: u32,
 }
 
-impl Default for AsteroidCount {
+impl Default for CompositionWeights {
+    /// Nothing but rock, as in the original arcade game.
     fn default() -> Self {
         Self {
-            max_asteroids: 8,
-            current_count: 0,
+            rock: 1,
+            ice: 0,
+            metal: 0,
+            crystal: 0,
         }
     }
 }
 
-pub fn spawn_asteroid_system(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-    mut spawn_timer: ResMut<AsteroidSpawnTimer>,
-    mut asteroid_count: ResMut<AsteroidCount>,
-    time: Res<Time>,
-    windows: Query<&Window>,
-) {
-    spawn_timer.0.tick(time.delta());
-    
-    if spawn_timer.0.finished() && asteroid_count.current_count < asteroid_count.max_asteroids {
-        if let Ok(window) = windows.get_single() {
-            spawn_asteroid_at_edge(&mut commands, &mut meshes, &mut materials, window, AsteroidSize::Large);
-            asteroid_count.current_count += 1;
+impl CompositionWeights {
+    /// The weight of a single composition.
+    pub fn weight(&self, composition: Composition) -> u32 {
+        match composition {
+            Composition::Rock => self.rock,
+            Composition::Ice => self.ice,
+            Composition::Metal => self.metal,
+            Composition::Crystal => self.crystal,
+        }
+    }
+
+    /// Draws a composition according to the weights.
+    pub fn pick(&self, rng: &mut impl Rng) -> Composition {
+        let total: u32 = Composition::ALL.iter().map(|composition| self.weight(*composition)).sum();
+        if total == 0 {
+            return Composition::Rock;
+        }
+
+        let mut roll = rng.gen_range(0..total);
+        for composition in Composition::ALL {
+            let weight = self.weight(composition);
+            if roll < weight {
+                return composition;
+            }
+            roll -= weight;
+        }
+        unreachable!("the roll is below the total weight")
+    }
+}
+
+/// Fewest corners an asteroid outline can have
+pub const MIN_OUTLINE_VERTICES: usize = 9;
+/// Most corners an asteroid outline can have
+pub const MAX_OUTLINE_VERTICES: usize = 13;
+/// How far a corner may stray from the mean radius, as a fraction of it
+pub const OUTLINE_JAGGEDNESS: f32 = 0.35;
+
+/// Generates a jagged outline for an asteroid of the given mean radius.
+///
+/// Corners go counter-clockwise around the origin with jittered angles and
+/// distances, rescaled so their mean distance is exactly `radius`. Every
+/// corner is visible from the origin, so the outline never crosses itself
+/// and `outline_mesh` can fan it out from the center.
+pub fn jagged_outline(radius: f32, rng: &mut impl Rng) -> Vec<Vec2> {
+    let vertex_count = rng.gen_range(MIN_OUTLINE_VERTICES..=MAX_OUTLINE_VERTICES);
+    let step = std::f32::consts::TAU / vertex_count as f32;
+
+    let corners: Vec<(f32, f32)> = (0..vertex_count)
+        .map(|index| {
+            let angle = (index as f32 + rng.gen_range(-0.3..0.3)) * step;
+            let distance = 1.0 + rng.gen_range(-OUTLINE_JAGGEDNESS..OUTLINE_JAGGEDNESS);
+            (angle, distance)
+        })
+        .collect();
+    let mean = corners.iter().map(|(_, distance)| distance).sum::<f32>() / vertex_count as f32;
+
+    corners
+        .into_iter()
+        .map(|(angle, distance)| Vec2::from_angle(angle) * distance / mean * radius)
+        .collect()
+}
+
+/// Distance from the center to the farthest corner of an outline.
+///
+/// The mean radius undersizes a jagged rock, so this is what bounds it for
+/// seam contacts and spawn safety.
+pub fn outline_radius(outline: &[Vec2]) -> f32 {
+    outline.iter().map(|corner| corner.length()).fold(0.0, f32::max)
+}
+
+/// Builds a flat mesh filling an outline from `jagged_outline`, as a triangle fan around the origin.
+pub fn outline_mesh(outline: &[Vec2]) -> Mesh {
+    let extent = outline.iter().map(|corner| corner.length()).fold(0.0, f32::max);
+
+    let mut positions = vec![[0.0, 0.0, 0.0]];
+    positions.extend(outline.iter().map(|corner| [corner.x, corner.y, 0.0]));
+    let normals = vec![[0.0, 0.0, 1.0]; positions.len()];
+    let uvs: Vec<[f32; 2]> = positions
+        .iter()
+        .map(|[x, y, _]| [0.5 + x / (2.0 * extent), 0.5 - y / (2.0 * extent)])
+        .collect();
+
+    let corner_count = outline.len() as u32;
+    let indices = (0..corner_count)
+        .flat_map(|corner| [0, corner + 1, (corner + 1) % corner_count + 1])
+        .collect();
+
+    Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+}
+
+/// Builds a collider matching an outline from `jagged_outline`.
+///
+/// The outline is concave wherever a corner dips inward, so it is split into
+/// convex pieces rather than wrapped in a hull that would catch bullets
+/// passing through the dents.
+pub fn outline_collider(outline: &[Vec2]) -> Collider {
+    let corner_count = outline.len() as u32;
+    let edges = (0..corner_count)
+        .map(|corner| [corner, (corner + 1) % corner_count])
+        .collect();
+    Collider::convex_decomposition(outline.to_vec(), edges)
+}
+
+/// Speed at which fragments fly apart from each other, on top of the parent's own motion
+pub const SEPARATION_SPEED: f32 = 60.0;
+
+/// Spin an off-center hit on the very edge adds to the fragments, in radians per second
+pub const MAX_IMPACT_SPIN: f32 = 3.0;
+
+/// The moment an asteroid breaks up: its motion and where the bullet struck.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Breakup {
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+    pub mass: f32,
+    /// Mean radius of the parent
+    pub radius: f32,
+    /// Direction the bullet was travelling in
+    pub impact_direction: Vec2,
+    /// Where the bullet struck, relative to the parent's center
+    pub impact_offset: Vec2,
+}
+
+/// How one fragment leaves a `Breakup`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FragmentMotion {
+    /// Spawn position relative to the parent's center
+    pub offset: Vec2,
+    pub velocity: Vec2,
+    pub angular_velocity: f32,
+    pub mass: f32,
+}
+
+impl Breakup {
+    /// Splits the parent's motion between `count` equal fragments of `fragment_radius`.
+    ///
+    /// The fragments share the parent's mass equally and spread evenly around
+    /// its center, starting perpendicular to the bullet, just touching each
+    /// other. Each keeps the parent's velocity plus the rigid-body velocity of
+    /// its spot on the spinning parent, and is pushed outward at `SEPARATION_SPEED`.
+    /// The pushes cancel out, so the fragments carry exactly the parent's
+    /// mass and momentum. A hit off center adds spin in the direction the
+    /// bullet would turn the rock.
+    pub fn fragments(&self, count: usize, fragment_radius: f32) -> Vec<FragmentMotion> {
+        if count == 0 {
+            return Vec::new();
         }
+
+        let direction = self.impact_direction.try_normalize().unwrap_or(Vec2::Y);
+        let lever = self.impact_offset.perp_dot(direction) / self.radius;
+        let angular_velocity = self.angular_velocity + MAX_IMPACT_SPIN * lever.clamp(-1.0, 1.0);
+
+        // Far enough out that neighbouring fragments do not overlap
+        let spread = std::f32::consts::TAU / count as f32;
+        let distance = fragment_radius / (spread / 2.0).sin().max(0.5);
+
+        (0..count)
+            .map(|index| {
+                let outward = direction.perp().rotate(Vec2::from_angle(spread * index as f32));
+                let offset = outward * distance;
+                FragmentMotion {
+                    offset,
+                    velocity: self.velocity
+                        + self.angular_velocity * offset.perp()
+                        + outward * SEPARATION_SPEED,
+                    angular_velocity,
+                    mass: self.mass / count as f32,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Live census of the asteroids in the world, broken down by size.
+///
+/// Kept up to date by the `count_spawned_asteroid` and `count_despawned_asteroid`
+/// observers, so every way an asteroid can appear or disappear is counted
+/// without the spawning and despawning systems having to report it.
+/// An asteroid's size never changes once it is spawned.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct AsteroidPopulation {
+    counts: [usize; 3],
+}
+
+impl AsteroidPopulation {
+    /// Number of asteroids of the given size
+    pub fn count(&self, size: AsteroidSize) -> usize {
+        self.counts[size as usize]
+    }
+
+    /// Number of asteroids of every size
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Combined mass of every asteroid, at the default density
+    pub fn mass(&self) -> f32 {
+        AsteroidSize::ALL
+            .into_iter()
+            .map(|size| self.count(size) as f32 * size.mass())
+            .sum()
     }
 }
 
+/// Counts an asteroid into the `AsteroidPopulation` as it is spawned.
+pub fn count_spawned_asteroid(
+    trigger: Trigger<OnAdd, Asteroid>,
+    asteroids: Query<&Asteroid>,
+    mut population: ResMut<AsteroidPopulation>,
+) {
+    if let Ok(asteroid) = asteroids.get(trigger.entity()) {
+        population.counts[asteroid.size as usize] += 1;
+    }
+}
+
+/// Counts an asteroid out of the `AsteroidPopulation`, however it is despawned.
+pub fn count_despawned_asteroid(
+    trigger: Trigger<OnRemove, Asteroid>,
+    asteroids: Query<&Asteroid>,
+    mut population: ResMut<AsteroidPopulation>,
+) {
+    if let Ok(asteroid) = asteroids.get(trigger.entity()) {
+        let count = &mut population.counts[asteroid.size as usize];
+        *count = count.saturating_sub(1);
+    }
+}
+
+/// Spawns an asteroid on the wrap seam, heading into the field.
+///
+/// Positions and velocities are redrawn until the asteroid's path keeps
+/// clear of every ship in `ships`, as `SpawnSafety` decides.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_asteroid_at_edge(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
-    window: &Window,
+    bounds: &WorldBounds,
+    safety: &SpawnSafety,
+    ships: &[MovingCircle],
     size: AsteroidSize,
+    composition: Composition,
+    speed_multiplier: f32,
+    rng: &mut impl Rng,
 ) {
-    let mut rng = thread_rng();
-    let radius = size.radius();
-    
-    let (spawn_x, spawn_y) = {
-        let margin = radius + 50.0;
-        let width = window.width();
-        let height = window.height();
-        
-        let edge = rng.gen_range(0..4);
-        match edge {
-            0 => (rng.gen_range(-width/2.0 - margin..width/2.0 + margin), height/2.0 + margin), // Top
-            1 => (width/2.0 + margin, rng.gen_range(-height/2.0 - margin..height/2.0 + margin)), // Right
-            2 => (rng.gen_range(-width/2.0 - margin..width/2.0 + margin), -height/2.0 - margin), // Bottom
-            _ => (-width/2.0 - margin, rng.gen_range(-height/2.0 - margin..height/2.0 + margin)), // Left
-        }
-    };
-    
-    let velocity = Vec2::new(
-        rng.gen_range(-100.0..100.0),
-        rng.gen_range(-100.0..100.0),
-    );
-    
+    // The outline comes first, so the safety check knows how far the rock really reaches
+    let outline = jagged_outline(size.radius(), rng);
+    let radius = outline_radius(&outline);
+
+    let (position, velocity) = safety.choose(bounds, ships, |_| {
+        // Spawn right on the wrap seam, its ghost covers the half poking onto the opposite edge
+        let position = {
+            let extents = bounds.wrap_extents();
+
+            let edge = rng.gen_range(0..4);
+            match edge {
+                0 => Vec2::new(rng.gen_range(-extents.x..extents.x), extents.y), // Top
+                1 => Vec2::new(extents.x, rng.gen_range(-extents.y..extents.y)), // Right
+                2 => Vec2::new(rng.gen_range(-extents.x..extents.x), -extents.y), // Bottom
+                _ => Vec2::new(-extents.x, rng.gen_range(-extents.y..extents.y)), // Left
+            }
+        };
+
+        let velocity = Vec2::new(
+            rng.gen_range(-100.0..100.0),
+            rng.gen_range(-100.0..100.0),
+        ) * speed_multiplier;
+
+        let circle = MovingCircle {
+            position,
+            velocity,
+            radius,
+        };
+        ((position, velocity), circle)
+    });
+
     let angular_velocity = rng.gen_range(-2.0..2.0);
-    
-    let asteroid_mesh = meshes.add(Circle::new(radius));
-    let asteroid_material = materials.add(Color::srgb(0.7, 0.7, 0.7));
-    
+
+    spawn_asteroid(
+        commands,
+        meshes,
+        materials,
+        Asteroid::new(size, composition),
+        position,
+        velocity,
+        angular_velocity,
+        size.mass(),
+        outline,
+    );
+}
+
+/// Spawns an asteroid body with the given outline.
+///
+/// Outlines are drawn from the gameplay stream, since the shape decides what
+/// the asteroid collides with. The collider's density is set so the body
+/// weighs `mass`.
+#[allow(clippy::too_many_arguments)]
+fn spawn_asteroid(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    asteroid: Asteroid,
+    position: Vec2,
+    velocity: Vec2,
+    angular_velocity: f32,
+    mass: f32,
+    outline: Vec<Vec2>,
+) {
+    let density = mass / asteroid.size.mass();
+    let bounding_radius = outline_radius(&outline);
+
+    let asteroid_mesh = meshes.add(outline_mesh(&outline));
+    let asteroid_material = materials.add(asteroid.composition.color());
+
     commands.spawn((
-        Asteroid { size },
+        asteroid,
         ColorMesh2dBundle {
             mesh: asteroid_mesh.into(),
             material: asteroid_material,
-            transform: Transform::from_translation(Vec3::new(spawn_x, spawn_y, 0.0)),
+            transform: Transform::from_translation(position.extend(0.0)),
             ..default()
         },
         RigidBody::Dynamic,
-        Collider::circle(radius),
+        Position(position),
+        outline_collider(&outline),
+        ColliderDensity(density),
+        BoundingRadius(bounding_radius),
         LinearVelocity(velocity),
         AngularVelocity(angular_velocity),
+        ScreenWrap,
     ));
 }
 
-pub fn wrap_asteroids(
-    mut asteroids: Query<&mut Transform, With<Asteroid>>,
-    windows: Query<&Window>,
-) {
-    if let Ok(window) = windows.get_single() {
-        let width = window.width();
-        let height = window.height();
-        let margin = 100.0;
-        
-        for mut transform in asteroids.iter_mut() {
-            let pos = &mut transform.translation;
-            
-            if pos.x > width/2.0 + margin {
-                pos.x = -width/2.0 - margin;
-            } else if pos.x < -width/2.0 - margin {
-                pos.x = width/2.0 + margin;
-            }
-            
-            if pos.y > height/2.0 + margin {
-                pos.y = -height/2.0 - margin;
-            } else if pos.y < -height/2.0 - margin {
-                pos.y = height/2.0 + margin;
-            }
-        }
-    }
+/// An asteroid that ran out of hit points this tick.
+///
+/// Sent exactly once per destroyed asteroid by `bullet_asteroid_collision_system`,
+/// after the asteroid itself has been despawned. `break_up_asteroids` turns
+/// it into points, fragments, loot and an explosion.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct AsteroidDestroyed {
+    /// The asteroid as it was when the final shot landed
+    pub asteroid: Asteroid,
+    pub position: Vec2,
+    /// Who fired the final shot
+    pub faction: Faction,
+    pub breakup: Breakup,
+}
+
+/// Sorts a tick's bullet-asteroid contacts and drops duplicates.
+///
+/// avian2d and `seam_contacts` can both report a pair, and a pair may be
+/// reported more than once, but each contact must only be resolved once.
+/// Sorting also resolves the contacts in the same order on every run.
+pub fn unique_hits(mut hits: Vec<(Entity, Entity)>) -> Vec<(Entity, Entity)> {
+    hits.sort_unstable();
+    hits.dedup();
+    hits
 }
 
+/// Resolves bullet hits on asteroids.
+///
+/// Every bullet is spent on the first asteroid it hits, unless it can still
+/// pierce through, and every hit takes one hit point. An asteroid out of hit
+/// points is despawned and announced with a single `AsteroidDestroyed`; later
+/// hits on it in the same tick are ignored and those bullets fly on.
+///
+/// Hits come from two sources: contacts reported by avian2d, and pairs that
+/// only touch across a wrap seam, which avian2d cannot see.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn bullet_asteroid_collision_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut collision_events: EventReader<CollisionStarted>,
-    bullets: Query<Entity, With<Bullet>>,
-    asteroids: Query<(Entity, &Transform, &Asteroid)>,
-    mut asteroid_count: ResMut<AsteroidCount>,
-    windows: Query<&Window>,
+    bounds: Res<WorldBounds>,
+    mut rng: ResMut<GameRng>,
+    mut bullets: Query<(
+        Entity,
+        &mut Bullet,
+        &Faction,
+        &Position,
+        &BoundingRadius,
+        &LinearVelocity,
+    )>,
+    mut asteroids: Query<(
+        Entity,
+        &mut Asteroid,
+        &Position,
+        &BoundingRadius,
+        &LinearVelocity,
+        &AngularVelocity,
+        &ColliderDensity,
+    )>,
+    mut destroyed_events: EventWriter<AsteroidDestroyed>,
 ) {
-    for CollisionStarted(entity1, entity2) in collision_events.read() {
-        let (bullet_entity, asteroid_entity) = if bullets.contains(*entity1) && asteroids.contains(*entity2) {
-            (*entity1, *entity2)
-        } else if bullets.contains(*entity2) && asteroids.contains(*entity1) {
-            (*entity2, *entity1)
-        } else {
+    let mut hits: Vec<(Entity, Entity)> = collision_events
+        .read()
+        .filter_map(|CollisionStarted(entity1, entity2)| {
+            if bullets.contains(*entity1) && asteroids.contains(*entity2) {
+                Some((*entity1, *entity2))
+            } else if bullets.contains(*entity2) && asteroids.contains(*entity1) {
+                Some((*entity2, *entity1))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let bullet_circles: Vec<_> = bullets
+        .iter()
+        .map(|(entity, _, _, position, radius, _)| (entity, position.0, radius.0))
+        .collect();
+    let asteroid_circles: Vec<_> = asteroids
+        .iter()
+        .map(|(entity, _, position, radius, ..)| (entity, position.0, radius.0))
+        .collect();
+    hits.extend(seam_contacts(&bounds, &bullet_circles, &asteroid_circles));
+
+    // Entities already used up this tick, whose despawn is still queued
+    let mut consumed: HashSet<Entity> = HashSet::new();
+
+    for (bullet_entity, asteroid_entity) in unique_hits(hits) {
+        if consumed.contains(&bullet_entity) || consumed.contains(&asteroid_entity) {
+            continue;
+        }
+        let (
+            Ok((_, mut bullet, faction, bullet_position, _, bullet_velocity)),
+            Ok((_, mut asteroid, asteroid_position, _, velocity, angular_velocity, density)),
+        ) = (bullets.get_mut(bullet_entity), asteroids.get_mut(asteroid_entity))
+        else {
             continue;
         };
-        
-        if let Ok((_, transform, asteroid)) = asteroids.get(asteroid_entity) {
-            let position = transform.translation.truncate();
-            let size_radius = asteroid.size.radius();
-            
+        // A piercing bullet still on its way out of an asteroid it already hit
+        if bullet.struck.contains(&asteroid_entity) {
+            continue;
+        }
+
+        if bullet.pierce > 0 {
+            bullet.pierce -= 1;
+            bullet.struck.push(asteroid_entity);
+        } else {
             commands.entity(bullet_entity).despawn();
-            commands.entity(asteroid_entity).despawn();
-            asteroid_count.current_count -= 1;
-            
-            particles::spawn_asteroid_destruction_particles(
+            consumed.insert(bullet_entity);
+        }
+        let breakup = Breakup {
+            velocity: velocity.0,
+            angular_velocity: angular_velocity.0,
+            mass: density.0 * asteroid.size.mass(),
+            radius: asteroid.size.radius(),
+            impact_direction: bullet_velocity.0,
+            // Measured around the torus, so hits across a seam land on the near side
+            impact_offset: bounds.delta(asteroid_position.0, bullet_position.0),
+        };
+        let destroyed = strike_asteroid(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut destroyed_events,
+            asteroid_entity,
+            &mut asteroid,
+            asteroid_position.0,
+            *faction,
+            breakup,
+            &mut rng.cosmetic,
+        );
+        if destroyed {
+            consumed.insert(asteroid_entity);
+        }
+    }
+}
+
+/// Takes one hit point off an asteroid and returns true if that destroyed it.
+///
+/// A dent only throws a few sparks. A destroyed asteroid is despawned and
+/// announced with an `AsteroidDestroyed`, so callers must make sure they
+/// strike it no more than once after that.
+#[allow(clippy::too_many_arguments)]
+pub fn strike_asteroid(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    destroyed_events: &mut EventWriter<AsteroidDestroyed>,
+    entity: Entity,
+    asteroid: &mut Asteroid,
+    position: Vec2,
+    faction: Faction,
+    breakup: Breakup,
+    rng: &mut impl Rng,
+) -> bool {
+    asteroid.hit_points = asteroid.hit_points.saturating_sub(1);
+    if asteroid.hit_points > 0 {
+        // A dent, not a break: a few sparks in the asteroid's own color
+        particles::spawn_explosion_particles(
+            commands,
+            meshes,
+            materials,
+            position,
+            6,
+            asteroid.composition.color(),
+            rng,
+        );
+        return false;
+    }
+
+    commands.entity(entity).despawn();
+    destroyed_events.send(AsteroidDestroyed {
+        asteroid: *asteroid,
+        position,
+        faction,
+        breakup,
+    });
+    true
+}
+
+/// Plays out every `AsteroidDestroyed` of the tick.
+///
+/// The shooter is awarded the asteroid's points, unless a saucer fired the
+/// shot. The asteroid splits into fragments of the same composition, as its
+/// `Composition` dictates, and metal leaves loot behind. The fragments carry
+/// on with the parent's mass and momentum, as worked out by `Breakup`.
+pub fn break_up_asteroids(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut destroyed_events: EventReader<AsteroidDestroyed>,
+    mut rng: ResMut<GameRng>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    for destroyed in destroyed_events.read() {
+        let AsteroidDestroyed { asteroid, position, faction, breakup } = *destroyed;
+
+        // Rocks shot down by saucers are not the players' doing
+        if let Faction::Player(player) = faction {
+            score_events.send(ScoreEvent {
+                player,
+                points: asteroid.points(),
+            });
+        }
+
+        particles::spawn_asteroid_destruction_particles(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            position,
+            asteroid.size.radius(),
+            &mut rng.cosmetic,
+        );
+
+        let composition = asteroid.composition;
+        if let Some(smaller_size) = composition.fragment_size(asteroid.size) {
+            let fragment_count = composition.fragment_count();
+            for motion in breakup.fragments(fragment_count, smaller_size.radius()) {
+                spawn_asteroid_fragment(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    position,
+                    smaller_size,
+                    composition,
+                    motion,
+                    &mut rng.gameplay,
+                );
+            }
+        }
+        if composition.drops_loot() {
+            loot::spawn_loot(
                 &mut commands,
                 &mut meshes,
                 &mut materials,
                 position,
-                size_radius,
+                &mut rng.gameplay,
             );
-            
-            if let Some(smaller_size) = asteroid.size.split() {
-                if let Ok(_window) = windows.get_single() {
-                    for _ in 0..2 {
-                        spawn_asteroid_fragment(
-                            &mut commands,
-                            &mut meshes,
-                            &mut materials,
-                            position,
-                            smaller_size,
-                        );
-                        asteroid_count.current_count += 1;
-                    }
-                }
-            }
         }
     }
 }
 
+/// Spawns one fragment of an asteroid that broke up at `position`, moving as `motion` says.
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_asteroid_fragment(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
     position: Vec2,
     size: AsteroidSize,
+    composition: Composition,
+    motion: FragmentMotion,
+    rng: &mut impl Rng,
 ) {
-    let mut rng = thread_rng();
-    let radius = size.radius();
-    
-    let velocity = Vec2::new(
-        rng.gen_range(-80.0..80.0),
-        rng.gen_range(-80.0..80.0),
+    let outline = jagged_outline(size.radius(), rng);
+    spawn_asteroid(
+        commands,
+        meshes,
+        materials,
+        Asteroid::new(size, composition),
+        position + motion.offset,
+        motion.velocity,
+        motion.angular_velocity,
+        motion.mass,
+        outline,
     );
-    
-    let angular_velocity = rng.gen_range(-3.0..3.0);
-    
-    let asteroid_mesh = meshes.add(Circle::new(radius));
-    let asteroid_material = materials.add(Color::srgb(0.7, 0.7, 0.7));
-    
-    commands.spawn((
-        Asteroid { size },
-        ColorMesh2dBundle {
-            mesh: asteroid_mesh.into(),
-            material: asteroid_material,
-            transform: Transform::from_translation(Vec3::new(position.x, position.y, 0.0)),
-            ..default()
-        },
-        RigidBody::Dynamic,
-        Collider::circle(radius),
-        LinearVelocity(velocity),
-        AngularVelocity(angular_velocity),
-    ));
 }
 
-pub fn despawn_asteroids(
-    mut commands: Commands,
-    asteroids: Query<Entity, With<Asteroid>>,
-    mut asteroid_count: ResMut<AsteroidCount>,
-) {
+pub fn despawn_asteroids(mut commands: Commands, asteroids: Query<Entity, With<Asteroid>>) {
     for entity in asteroids.iter() {
         commands.entity(entity).despawn();
     }
-    asteroid_count.current_count = 0;
 }
\ No newline at end of file
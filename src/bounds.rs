@@ -0,0 +1,282 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::sprite::Mesh2dHandle;
+use bevy::window::PrimaryWindow;
+
+use crate::physics::{
+    PhysicalTranslation, PreviousPhysicalTranslation, SCREEN_HEIGHT, SCREEN_WIDTH, WRAP_MARGIN,
+};
+
+/// The toroidal playfield every wrapping entity lives on.
+///
+/// The visible area spans `-half_extents..half_extents` around the origin.
+/// Entities wrap once they are `margin` past an edge, so the world repeats
+/// with a period of `2 * (half_extents + margin)` on each axis.
+///
+/// Starts at the default screen size and follows the primary window when there
+/// is one, so headless simulations keep a well-defined world.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct WorldBounds {
+    pub half_extents: Vec2,
+    pub margin: f32,
+}
+
+impl Default for WorldBounds {
+    fn default() -> Self {
+        Self::new(SCREEN_WIDTH, SCREEN_HEIGHT)
+    }
+}
+
+impl WorldBounds {
+    /// Creates bounds for a visible area of the given size with the default wrap margin.
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            half_extents: Vec2::new(width, height) / 2.0,
+            margin: WRAP_MARGIN,
+        }
+    }
+
+    /// Distance from the origin to the seam where entities wrap, per axis.
+    pub fn wrap_extents(&self) -> Vec2 {
+        self.half_extents + Vec2::splat(self.margin)
+    }
+
+    /// Length of one full lap around the world, per axis.
+    pub fn period(&self) -> Vec2 {
+        self.wrap_extents() * 2.0
+    }
+
+    /// Maps a position that crossed a seam back into the world.
+    ///
+    /// Positions already inside are returned unchanged, and any overshoot is
+    /// carried over to the other side.
+    pub fn wrap(&self, position: Vec2) -> Vec2 {
+        let extents = self.wrap_extents();
+        let period = self.period();
+        let mut wrapped = position;
+
+        if position.x > extents.x || position.x < -extents.x {
+            wrapped.x = (position.x + extents.x).rem_euclid(period.x) - extents.x;
+        }
+        if position.y > extents.y || position.y < -extents.y {
+            wrapped.y = (position.y + extents.y).rem_euclid(period.y) - extents.y;
+        }
+
+        wrapped
+    }
+
+    /// Shortest displacement from `from` to `to`, crossing a seam when that is shorter.
+    pub fn delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let period = self.period();
+        let direct = to - from;
+        direct - period * (direct / period).round()
+    }
+
+    /// Distance between two points measured around the torus.
+    pub fn distance(&self, a: Vec2, b: Vec2) -> f32 {
+        self.delta(a, b).length()
+    }
+
+    /// Offset that moves a circle poking across a seam onto the opposite edge, per axis.
+    ///
+    /// Axes on which the circle stays inside the world are zero.
+    pub fn seam_offset(&self, position: Vec2, radius: f32) -> Vec2 {
+        let extents = self.wrap_extents();
+        let period = self.period();
+        let axis = |position: f32, extent: f32, period: f32| {
+            if position + radius > extent {
+                -period
+            } else if position - radius < -extent {
+                period
+            } else {
+                0.0
+            }
+        };
+
+        Vec2::new(
+            axis(position.x, extents.x, period.x),
+            axis(position.y, extents.y, period.y),
+        )
+    }
+}
+
+/// Marks an entity as living on the torus described by `WorldBounds`.
+///
+/// Rigid bodies wrap through their avian2d `Position`, everything else
+/// through its `Transform`.
+#[derive(Component, Debug, Clone, Copy, Default)]
+pub struct ScreenWrap;
+
+/// Radius of a circle enclosing the entity in world units.
+///
+/// Wrapping entities with a radius get ghost copies while they straddle a
+/// seam and take part in `seam_contacts`.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct BoundingRadius(pub f32);
+
+/// A copy of another entity's mesh drawn on the opposite side of a seam.
+///
+/// Every source gets three ghosts: one across the vertical seam, one across the
+/// horizontal seam and one across both for the corners. A ghost is only visible
+/// while its source pokes across all of the seams it covers.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Ghost {
+    pub source: Entity,
+    /// Which axes this ghost is shifted along
+    pub axes: BVec2,
+}
+
+/// Keeps `WorldBounds` in sync with the primary window's size.
+///
+/// Without a window the bounds keep their current value.
+pub fn update_world_bounds(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    mut bounds: ResMut<WorldBounds>,
+) {
+    let Ok(window) = windows.get_single() else {
+        return;
+    };
+
+    let half_extents = Vec2::new(window.width(), window.height()) / 2.0;
+    // Only touch the resource on a real resize so change detection stays meaningful
+    if bounds.half_extents != half_extents {
+        bounds.half_extents = half_extents;
+    }
+}
+
+/// Wraps rigid bodies that crossed a seam.
+///
+/// This system:
+/// 1. Wraps the avian2d position onto the opposite side
+/// 2. Moves the transform along so the old position is not drawn for a frame
+/// 3. Moves both ship physics snapshots, if present, so interpolation does not streak
+///
+/// Runs in `FixedPostUpdate` after avian2d has stepped the simulation.
+#[allow(clippy::type_complexity)]
+pub fn wrap_bodies(
+    bounds: Res<WorldBounds>,
+    mut query: Query<
+        (
+            &mut Position,
+            &mut Transform,
+            Option<&mut PhysicalTranslation>,
+            Option<&mut PreviousPhysicalTranslation>,
+        ),
+        With<ScreenWrap>,
+    >,
+) {
+    for (mut position, mut transform, translation, prev_translation) in query.iter_mut() {
+        let wrapped = bounds.wrap(position.0);
+        if wrapped == position.0 {
+            continue;
+        }
+
+        position.0 = wrapped;
+        transform.translation = wrapped.extend(transform.translation.z);
+
+        if let (Some(mut translation), Some(mut prev_translation)) = (translation, prev_translation)
+        {
+            translation.0 = wrapped.extend(translation.0.z);
+            prev_translation.0 = translation.0;
+        }
+    }
+}
+
+/// Wraps entities without a rigid body, such as particles, that crossed a seam.
+pub fn wrap_transforms(
+    bounds: Res<WorldBounds>,
+    mut query: Query<&mut Transform, (With<ScreenWrap>, Without<Position>)>,
+) {
+    for mut transform in query.iter_mut() {
+        let position = transform.translation.truncate();
+        let wrapped = bounds.wrap(position);
+        if wrapped != position {
+            transform.translation = wrapped.extend(transform.translation.z);
+        }
+    }
+}
+
+/// Spawns the ghost copies for newly added wrapping entities.
+#[allow(clippy::type_complexity)]
+pub fn spawn_ghosts(
+    mut commands: Commands,
+    sources: Query<
+        (Entity, &Mesh2dHandle, &Handle<ColorMaterial>),
+        (Added<BoundingRadius>, With<ScreenWrap>),
+    >,
+) {
+    for (source, mesh, material) in sources.iter() {
+        for axes in [BVec2::new(true, false), BVec2::new(false, true), BVec2::TRUE] {
+            commands.spawn((
+                Ghost { source, axes },
+                ColorMesh2dBundle {
+                    mesh: mesh.clone(),
+                    material: material.clone(),
+                    visibility: Visibility::Hidden,
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+/// Moves ghosts onto the opposite side of the seams their source straddles.
+///
+/// This system:
+/// 1. Despawns ghosts whose source no longer exists
+/// 2. Hides ghosts whose source does not poke across the seams they cover
+/// 3. Copies the source's rendered transform shifted by one period, and its
+///    visibility so blinking and hidden entities stay consistent
+///
+/// Runs in `PostUpdate` after render interpolation so ghosts never lag behind.
+pub fn update_ghosts(
+    mut commands: Commands,
+    bounds: Res<WorldBounds>,
+    sources: Query<(&Transform, &BoundingRadius, &Visibility), Without<Ghost>>,
+    mut ghosts: Query<(Entity, &Ghost, &mut Transform, &mut Visibility)>,
+) {
+    for (ghost_entity, ghost, mut transform, mut visibility) in ghosts.iter_mut() {
+        let Ok((source_transform, radius, source_visibility)) = sources.get(ghost.source) else {
+            commands.entity(ghost_entity).despawn();
+            continue;
+        };
+
+        let offset = bounds.seam_offset(source_transform.translation.truncate(), radius.0);
+        let straddles = (!ghost.axes.x || offset.x != 0.0) && (!ghost.axes.y || offset.y != 0.0);
+        if !straddles {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+
+        let shift = Vec2::select(ghost.axes, offset, Vec2::ZERO);
+        *transform = *source_transform;
+        transform.translation += shift.extend(0.0);
+        *visibility = *source_visibility;
+    }
+}
+
+/// Finds pairs of circles that touch only across a seam.
+///
+/// Direct contacts are reported by avian2d, so they are left out here and each
+/// touching pair is found by exactly one of the two paths. Every element is an
+/// `(entity, position, radius)` triple, and pairs come back in `(first, second)` order.
+pub fn seam_contacts(
+    bounds: &WorldBounds,
+    first: &[(Entity, Vec2, f32)],
+    second: &[(Entity, Vec2, f32)],
+) -> Vec<(Entity, Entity)> {
+    let mut contacts = Vec::new();
+
+    for &(first_entity, first_position, first_radius) in first {
+        for &(second_entity, second_position, second_radius) in second {
+            let reach = first_radius + second_radius;
+            if bounds.distance(first_position, second_position) < reach
+                && first_position.distance(second_position) >= reach
+            {
+                contacts.push((first_entity, second_entity));
+            }
+        }
+    }
+
+    contacts
+}
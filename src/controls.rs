@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+
+use crate::input::{Action, Binding, InputBindings};
+use crate::physics::{self, MoveDirection};
+use crate::player::{GameMode, MAX_PLAYERS};
+
+/// Everything one player asked their ship to do during one fixed tick.
+///
+/// Axes are stored as `i8` so a frame stays three bytes in a replay file:
+/// `AXIS_MAX` is full deflection and analog devices can use anything in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ControlFrame {
+    /// Forward thrust, negative values brake
+    pub thrust: i8,
+    /// Rotation, positive turns left
+    pub rotate: i8,
+    /// Bit set of the `ControlFrame::*` button flags
+    pub buttons: u8,
+}
+
+impl ControlFrame {
+    /// Axis value meaning full deflection
+    pub const AXIS_MAX: i8 = 127;
+
+    /// Held while the guns should fire
+    pub const FIRE: u8 = 1 << 0;
+    /// Set on the tick the ship should be reset to the center
+    pub const RESET: u8 = 1 << 1;
+    /// Set on the tick the ship should jump to hyperspace
+    pub const HYPERSPACE: u8 = 1 << 2;
+    /// Set on the tick the ship should switch to its next weapon
+    pub const SWITCH_WEAPON: u8 = 1 << 3;
+
+    /// Thrust as a value in `-1.0..=1.0`.
+    pub fn thrust_axis(&self) -> f32 {
+        (self.thrust as f32 / Self::AXIS_MAX as f32).clamp(-1.0, 1.0)
+    }
+
+    /// Rotation as a value in `-1.0..=1.0`.
+    pub fn rotate_axis(&self) -> f32 {
+        (self.rotate as f32 / Self::AXIS_MAX as f32).clamp(-1.0, 1.0)
+    }
+
+    /// Returns true if any of the given button flags are set.
+    pub fn pressed(&self, buttons: u8) -> bool {
+        self.buttons & buttons != 0
+    }
+}
+
+/// The `ControlFrame` of every player for one fixed tick.
+///
+/// Gameplay systems read their input from this resource rather than from the
+/// keyboard, so a tick can be driven just as well by a recorded replay. It is
+/// indexed by `Player::id`; players not taking part keep an empty frame.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PlayerControls(pub [ControlFrame; MAX_PLAYERS]);
+
+impl PlayerControls {
+    /// The given player's controls for this tick.
+    pub fn get(&self, player: usize) -> ControlFrame {
+        self.0[player]
+    }
+}
+
+/// Live input gathered every frame and handed to the next fixed tick.
+///
+/// Axes and held buttons are simply the latest state, but one-shot presses
+/// are kept in `pending` until a tick consumes them, so a quick tap is never
+/// lost on a frame that runs no fixed tick. Both are indexed by `Player::id`.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct ControlLatch {
+    pub held: [ControlFrame; MAX_PLAYERS],
+    pub pending: [u8; MAX_PLAYERS],
+}
+
+/// Reads the keyboard into the `ControlLatch` through each player's `InputBindings`.
+pub fn latch_keyboard_controls(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<InputBindings>,
+    mut latch: ResMut<ControlLatch>,
+) {
+    for player in 0..MAX_PLAYERS {
+        let binding = |action| Binding::new(player, action);
+
+        let mut frame = ControlFrame::default();
+        for direction in physics::get_pressed_directions(&keyboard_input, &bindings, player) {
+            match direction {
+                MoveDirection::Up => frame.thrust += ControlFrame::AXIS_MAX,
+                MoveDirection::Down => frame.thrust -= ControlFrame::AXIS_MAX,
+                MoveDirection::Left => frame.rotate += ControlFrame::AXIS_MAX,
+                MoveDirection::Right => frame.rotate -= ControlFrame::AXIS_MAX,
+            }
+        }
+        if bindings.pressed(binding(Action::Fire), &keyboard_input) {
+            frame.buttons |= ControlFrame::FIRE;
+        }
+        latch.held[player] = frame;
+
+        // A tap shorter than a tick still fires once
+        let pending = &mut latch.pending[player];
+        if bindings.just_pressed(binding(Action::Fire), &keyboard_input) {
+            *pending |= ControlFrame::FIRE;
+        }
+        if bindings.just_pressed(binding(Action::ResetShip), &keyboard_input) {
+            *pending |= ControlFrame::RESET;
+        }
+        if bindings.just_pressed(binding(Action::Hyperspace), &keyboard_input) {
+            *pending |= ControlFrame::HYPERSPACE;
+        }
+        if bindings.just_pressed(binding(Action::SwitchWeapon), &keyboard_input) {
+            *pending |= ControlFrame::SWITCH_WEAPON;
+        }
+    }
+}
+
+/// Builds this tick's `PlayerControls` from the latch.
+///
+/// Runs first in `FixedUpdate`, ahead of every system that reads the controls.
+/// Players not taking part in the session get an empty frame, whatever keys
+/// are held for them.
+pub fn sample_controls(
+    mut latch: ResMut<ControlLatch>,
+    mut controls: ResMut<PlayerControls>,
+    mode: Res<GameMode>,
+) {
+    for (player, frame) in controls.0.iter_mut().enumerate() {
+        *frame = ControlFrame::default();
+        if player < mode.player_count() {
+            *frame = latch.held[player];
+            frame.buttons |= latch.pending[player];
+        }
+    }
+    latch.pending = [0; MAX_PLAYERS];
+}
+
+/// Drops input left over from a previous session.
+pub fn reset_controls(mut latch: ResMut<ControlLatch>, mut controls: ResMut<PlayerControls>) {
+    *latch = ControlLatch::default();
+    *controls = PlayerControls::default();
+}
@@ -0,0 +1,237 @@
+use bevy::input::gamepad::{GamepadConnection, GamepadConnectionEvent};
+use bevy::prelude::*;
+
+use crate::controls::{ControlFrame, ControlLatch};
+use crate::input::InputBindings;
+use crate::player::{GameMode, MAX_PLAYERS};
+use crate::state::GameState;
+
+/// Held to fire, like the `Fire` key
+pub const FIRE_BUTTON: GamepadButtonType = GamepadButtonType::South;
+/// Cycles to the ship's next weapon, like the `SwitchWeapon` key
+pub const SWITCH_WEAPON_BUTTON: GamepadButtonType = GamepadButtonType::North;
+/// Jumps to hyperspace, like the `Hyperspace` key
+pub const HYPERSPACE_BUTTON: GamepadButtonType = GamepadButtonType::West;
+/// Resets the ship to the center, like the `ResetShip` key
+pub const RESET_BUTTON: GamepadButtonType = GamepadButtonType::Select;
+/// Starts a game from the main menu and pauses or resumes one in progress
+pub const START_BUTTON: GamepadButtonType = GamepadButtonType::Start;
+/// Picks the next game mode on the main menu, the weapon button is free there
+pub const MODE_BUTTON: GamepadButtonType = GamepadButtonType::North;
+
+/// How far sticks and triggers must move before they register.
+///
+/// Input past the deadzone is rescaled so the usable range still starts at
+/// zero and reaches full deflection, rather than jumping in at the deadzone's edge.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deadzones {
+    /// Radius of the left stick's deadzone, in `0.0..1.0`
+    pub stick: f32,
+    /// Travel of the triggers' deadzone, in `0.0..1.0`
+    pub trigger: f32,
+}
+
+impl Default for Deadzones {
+    fn default() -> Self {
+        Self {
+            stick: 0.15,
+            trigger: 0.1,
+        }
+    }
+}
+
+impl Deadzones {
+    /// Applies the stick deadzone to a raw stick position.
+    ///
+    /// The deadzone is radial, so a diagonal push registers as soon as it
+    /// leaves the circle instead of only once both axes clear it.
+    pub fn apply_stick(&self, raw: Vec2) -> Vec2 {
+        let length = raw.length();
+        if length <= self.stick {
+            return Vec2::ZERO;
+        }
+        let scaled = ((length - self.stick) / (1.0 - self.stick)).min(1.0);
+        raw / length * scaled
+    }
+
+    /// Applies the trigger deadzone to a raw trigger value in `0.0..=1.0`.
+    pub fn apply_trigger(&self, raw: f32) -> f32 {
+        if raw <= self.trigger {
+            return 0.0;
+        }
+        ((raw - self.trigger) / (1.0 - self.trigger)).min(1.0)
+    }
+}
+
+/// Which gamepad flies each player's ship, indexed by `Player::id`.
+///
+/// Gamepads take the first free slot as they connect, so the first one plugged
+/// in belongs to player one and the second to player two. When a gamepad is
+/// unplugged, a connected one without a slot takes its place if there is one.
+/// In one-player games every connected gamepad flies the only ship.
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct ActiveGamepads(pub [Option<Gamepad>; MAX_PLAYERS]);
+
+impl ActiveGamepads {
+    /// Every assigned gamepad along with the player it belongs to.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Gamepad)> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(player, gamepad)| Some((player, (*gamepad)?)))
+    }
+
+    fn contains(&self, gamepad: Gamepad) -> bool {
+        self.0.contains(&Some(gamepad))
+    }
+}
+
+/// Follows gamepads being plugged in and out.
+///
+/// Losing a player's gamepad mid-session pauses the game, so their ship does
+/// not drift into a rock while they reach for the cable.
+pub fn track_gamepads(
+    mut connection_events: EventReader<GamepadConnectionEvent>,
+    gamepads: Res<Gamepads>,
+    mut active: ResMut<ActiveGamepads>,
+    mode: Res<GameMode>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for event in connection_events.read() {
+        match &event.connection {
+            GamepadConnection::Connected(info) => {
+                if active.contains(event.gamepad) {
+                    continue;
+                }
+                if let Some(slot) = active.0.iter().position(Option::is_none) {
+                    info!(
+                        "Gamepad {} ({}) controls player {}",
+                        event.gamepad.id,
+                        info.name,
+                        slot + 1
+                    );
+                    active.0[slot] = Some(event.gamepad);
+                }
+            }
+            GamepadConnection::Disconnected => {
+                let Some(slot) = active.0.iter().position(|slot| *slot == Some(event.gamepad))
+                else {
+                    continue;
+                };
+                active.0[slot] = None;
+                active.0[slot] = gamepads
+                    .iter()
+                    .find(|gamepad| *gamepad != event.gamepad && !active.contains(*gamepad));
+                match active.0[slot] {
+                    Some(gamepad) => info!("Gamepad {} now controls player {}", gamepad.id, slot + 1),
+                    None => info!("Gamepad {} disconnected", event.gamepad.id),
+                }
+                if *state.get() == GameState::Playing && slot < mode.player_count() {
+                    next_state.set(GameState::Paused);
+                }
+            }
+        }
+    }
+}
+
+/// Adds each player's gamepad input to the `ControlLatch`.
+///
+/// Runs after the keyboard has been latched. The left stick and the triggers
+/// are analog: thrust and rotation follow how far they are pushed, and
+/// whichever of keyboard or gamepad deflects an axis further wins.
+pub fn latch_gamepad_controls(
+    active: Res<ActiveGamepads>,
+    mode: Res<GameMode>,
+    axes: Res<Axis<GamepadAxis>>,
+    button_axes: Res<Axis<GamepadButton>>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    bindings: Res<InputBindings>,
+    mut latch: ResMut<ControlLatch>,
+) {
+    for (slot, gamepad) in active.iter() {
+        let player = if mode.player_count() == 1 { 0 } else { slot };
+        latch_gamepad(
+            gamepad,
+            player,
+            bindings.deadzones,
+            &axes,
+            &button_axes,
+            &buttons,
+            &mut latch,
+        );
+    }
+}
+
+/// Adds one gamepad's input to a player's latched controls.
+fn latch_gamepad(
+    gamepad: Gamepad,
+    player: usize,
+    deadzones: Deadzones,
+    axes: &Axis<GamepadAxis>,
+    button_axes: &Axis<GamepadButton>,
+    buttons: &ButtonInput<GamepadButton>,
+    latch: &mut ControlLatch,
+) {
+    let axis = |axis_type| axes.get(GamepadAxis::new(gamepad, axis_type)).unwrap_or(0.0);
+    let stick = deadzones.apply_stick(Vec2::new(
+        axis(GamepadAxisType::LeftStickX),
+        axis(GamepadAxisType::LeftStickY),
+    ));
+    let trigger = |button_type| {
+        let value = button_axes
+            .get(GamepadButton::new(gamepad, button_type))
+            .unwrap_or(0.0);
+        deadzones.apply_trigger(value)
+    };
+    let thrust = stick.y + trigger(GamepadButtonType::RightTrigger2)
+        - trigger(GamepadButtonType::LeftTrigger2);
+
+    // Pushing the stick right turns the ship clockwise
+    let held = &mut latch.held[player];
+    held.thrust = strongest(held.thrust, to_axis(thrust));
+    held.rotate = strongest(held.rotate, to_axis(-stick.x));
+
+    let button = |button_type| GamepadButton::new(gamepad, button_type);
+    if buttons.pressed(button(FIRE_BUTTON)) {
+        held.buttons |= ControlFrame::FIRE;
+    }
+    let pending = &mut latch.pending[player];
+    if buttons.just_pressed(button(FIRE_BUTTON)) {
+        *pending |= ControlFrame::FIRE;
+    }
+    if buttons.just_pressed(button(RESET_BUTTON)) {
+        *pending |= ControlFrame::RESET;
+    }
+    if buttons.just_pressed(button(HYPERSPACE_BUTTON)) {
+        *pending |= ControlFrame::HYPERSPACE;
+    }
+    if buttons.just_pressed(button(SWITCH_WEAPON_BUTTON)) {
+        *pending |= ControlFrame::SWITCH_WEAPON;
+    }
+}
+
+/// Returns true on the frame any player's gamepad has `button_type` go down.
+pub fn just_pressed(
+    active: &ActiveGamepads,
+    buttons: &ButtonInput<GamepadButton>,
+    button_type: GamepadButtonType,
+) -> bool {
+    active
+        .iter()
+        .any(|(_, gamepad)| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+}
+
+/// Quantizes an axis value in `-1.0..=1.0` into a `ControlFrame` axis.
+fn to_axis(value: f32) -> i8 {
+    (value.clamp(-1.0, 1.0) * ControlFrame::AXIS_MAX as f32).round() as i8
+}
+
+/// Picks whichever of two axis values is deflected further.
+fn strongest(first: i8, second: i8) -> i8 {
+    if second.unsigned_abs() > first.unsigned_abs() {
+        second
+    } else {
+        first
+    }
+}
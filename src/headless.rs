@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use bevy::input::gamepad::{
+    GamepadAxisChangedEvent, GamepadButtonChangedEvent, GamepadConnection,
+    GamepadConnectionEvent, GamepadEvent, GamepadInfo,
+};
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::GamePlugin;
+use crate::asteroids::{Asteroid, Composition};
+use crate::highscores::HighScores;
+use crate::input::InputBindings;
+use crate::player::GameMode;
+use crate::replay::{Playback, Recording, Replay};
+use crate::rng::GameRng;
+use crate::state::GameState;
+use crate::waves::WAVE_INTERMISSION;
+
+/// The full game simulation without a window, renderer or asset server.
+///
+/// Built on `MinimalPlugins`, so it runs anywhere, including CI machines with
+/// no GPU or display. Time is advanced manually: every call to `step` moves
+/// the game forward by exact fixed ticks, so a given seed and input sequence
+/// always produces the same world.
+///
+/// High scores and key bindings start from the defaults and live in memory
+/// only, so the player's files are never read, moved or written.
+pub struct HeadlessGame {
+    app: App,
+    tick: Duration,
+}
+
+impl HeadlessGame {
+    /// Builds the headless app with the given RNG seed.
+    ///
+    /// Startup systems have already run when this returns, and the game is
+    /// sitting on the main menu.
+    pub fn new(seed: u64) -> Self {
+        Self::build(|app| {
+            app.insert_resource(GameRng::new(seed));
+        })
+    }
+
+    /// Builds a headless app that plays back a recorded session.
+    ///
+    /// The session has already started when this returns, and each `step`
+    /// plays one recorded tick. Once the recording runs out the game returns
+    /// to the main menu.
+    pub fn from_replay(replay: Replay) -> Self {
+        Self::build(|app| {
+            app.insert_resource(GameRng::new(replay.seed))
+                .insert_resource(replay.mode)
+                .insert_resource(replay.bounds)
+                .insert_resource(Playback::new(replay));
+        })
+    }
+
+    /// Starts recording every session into memory, available through `recording`.
+    pub fn record(&mut self) {
+        self.world_mut().insert_resource(Recording::in_memory());
+    }
+
+    /// The latest recorded session, if `record` was called.
+    pub fn recording(&self) -> Option<&Replay> {
+        self.world()
+            .get_resource::<Recording>()
+            .map(|recording| &recording.replay)
+    }
+
+    /// Creates the app, letting `setup` insert resources before the game plugin is added.
+    fn build(setup: impl FnOnce(&mut App)) -> Self {
+        let mut app = App::new();
+        // In place before the plugin is added, so it never reads or moves the player's files
+        app.insert_resource(HighScores::default())
+            .insert_resource(InputBindings::default());
+        setup(&mut app);
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            HierarchyPlugin,
+            InputPlugin,
+            StatesPlugin,
+        ))
+        // Meshes and materials are still created by the spawners, they are just never drawn
+        .init_resource::<Assets<Mesh>>()
+        .init_resource::<Assets<ColorMaterial>>()
+        .add_plugins(GamePlugin);
+
+        let tick = app.world().resource::<Time<Fixed>>().timestep();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(tick));
+        app.finish();
+        app.cleanup();
+
+        // The first update only starts the clock, so run it here to keep every step one tick long
+        app.update();
+
+        Self { app, tick }
+    }
+
+    /// Advances the simulation by the given number of fixed ticks.
+    ///
+    /// Each tick is one full app update that runs exactly one `FixedUpdate`.
+    pub fn step(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.app.update();
+        }
+    }
+
+    /// Advances one fixed tick plus `fraction` of another, left over on the fixed clock.
+    ///
+    /// A window's frames rarely land on a tick boundary, so rendered ship
+    /// transforms sit part-way between two ticks. Every later `step` keeps the
+    /// same overstep, letting tests check that gameplay never reads them.
+    pub fn step_with_overstep(&mut self, fraction: f32) {
+        let tick = self.tick;
+        self.world_mut()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(tick + tick.mul_f32(fraction)));
+        self.app.update();
+        self.world_mut()
+            .insert_resource(TimeUpdateStrategy::ManualDuration(tick));
+    }
+
+    /// Length of a single tick.
+    pub fn tick_duration(&self) -> Duration {
+        self.tick
+    }
+
+    /// Leaves the main menu and sets up a fresh session in the given mode.
+    pub fn start_game_with(&mut self, mode: GameMode) {
+        self.world_mut().insert_resource(mode);
+        self.start_game();
+    }
+
+    /// Leaves the main menu and sets up a fresh session in the current mode, one-player by default.
+    pub fn start_game(&mut self) {
+        self.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        self.step(1);
+    }
+
+    /// Ticks from the start of a session until the first wave's asteroids are on the field.
+    pub fn ticks_until_first_wave(&self) -> u32 {
+        (WAVE_INTERMISSION / self.tick.as_secs_f32()).ceil() as u32 + 1
+    }
+
+    /// Steps a freshly started session until the first wave's asteroids are on the field.
+    pub fn step_until_first_wave(&mut self) {
+        self.step(self.ticks_until_first_wave());
+    }
+
+    /// Makes every asteroid in the world a fresh one of `composition`, keeping its size and body.
+    ///
+    /// Only the composition changes, so the collider, mass and population
+    /// counts all still match. Returns the asteroids in entity order, so
+    /// scenarios built on a wave pick the same ones on every run.
+    pub fn recompose_asteroids(&mut self, composition: Composition) -> Vec<Entity> {
+        let world = self.world_mut();
+        let mut recolored = Vec::new();
+        let mut entities = Vec::new();
+        for (entity, mut asteroid, material) in world
+            .query::<(Entity, &mut Asteroid, Option<&Handle<ColorMaterial>>)>()
+            .iter_mut(world)
+        {
+            *asteroid = Asteroid::new(asteroid.size, composition);
+            recolored.extend(material.cloned());
+            entities.push(entity);
+        }
+        let mut materials = world.resource_mut::<Assets<ColorMaterial>>();
+        for material in recolored {
+            if let Some(material) = materials.get_mut(&material) {
+                material.color = composition.color();
+            }
+        }
+        entities.sort_unstable();
+        entities
+    }
+
+    /// Holds a key down from the next tick on, as if it came from a keyboard.
+    pub fn press(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Pressed);
+    }
+
+    /// Lets go of a key from the next tick on.
+    pub fn release(&mut self, key_code: KeyCode) {
+        self.send_key(key_code, ButtonState::Released);
+    }
+
+    /// Plugs in a gamepad with the given id, as if it came from the OS.
+    pub fn connect_gamepad(&mut self, id: usize) -> Gamepad {
+        let gamepad = Gamepad::new(id);
+        let info = GamepadInfo {
+            name: "Headless gamepad".to_string(),
+        };
+        self.send_gamepad_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected(info),
+        ));
+        gamepad
+    }
+
+    /// Unplugs a gamepad.
+    pub fn disconnect_gamepad(&mut self, gamepad: Gamepad) {
+        self.send_gamepad_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Disconnected,
+        ));
+    }
+
+    /// Moves a stick axis to `value`, in `-1.0..=1.0`, from the next tick on.
+    pub fn move_gamepad_axis(&mut self, gamepad: Gamepad, axis_type: GamepadAxisType, value: f32) {
+        self.send_gamepad_event(GamepadAxisChangedEvent::new(gamepad, axis_type, value));
+    }
+
+    /// Sets how far a button or trigger is pushed, in `0.0..=1.0`, from the next tick on.
+    pub fn set_gamepad_button(
+        &mut self,
+        gamepad: Gamepad,
+        button_type: GamepadButtonType,
+        value: f32,
+    ) {
+        // Like the gilrs backend, analog button values are written straight to the axis
+        self.world_mut()
+            .resource_mut::<Axis<GamepadButton>>()
+            .set(GamepadButton::new(gamepad, button_type), value);
+        self.send_gamepad_event(GamepadButtonChangedEvent::new(gamepad, button_type, value));
+    }
+
+    /// The state the game flow is currently in.
+    pub fn state(&self) -> GameState {
+        *self.world().resource::<State<GameState>>().get()
+    }
+
+    /// Read access to the simulated world for inspecting entities and resources.
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    /// Write access to the simulated world, for setting up scenarios.
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// The underlying app, for adding extra systems or resources.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    fn send_gamepad_event(&mut self, event: impl Into<GamepadEvent>) {
+        self.world_mut().send_event(event.into());
+    }
+
+    fn send_key(&mut self, key_code: KeyCode, state: ButtonState) {
+        // `just_pressed` is cleared at the start of every frame, so keys have to arrive as events
+        self.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+}
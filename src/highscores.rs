@@ -0,0 +1,387 @@
+use bevy::prelude::*;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::gamepad::{self, ActiveGamepads};
+use crate::score::Score;
+use crate::waves::Wave;
+
+/// Number of entries kept in the table
+pub const MAX_HIGH_SCORES: usize = 10;
+
+/// Number of letters in a set of initials
+pub const INITIALS_LENGTH: usize = 3;
+
+/// First line of every high-score file, followed by the format version
+const FILE_HEADER: &str = "asteroids-highscores";
+
+/// Format version written by this build
+const FILE_VERSION: u32 = 1;
+
+/// A single row of the high-score table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighScoreEntry {
+    pub score: u32,
+    pub initials: String,
+    /// Seconds since the Unix epoch when the score was set
+    pub timestamp: u64,
+    pub wave: u32,
+}
+
+impl HighScoreEntry {
+    /// Formats the entry's timestamp as a `YYYY-MM-DD` date in UTC.
+    pub fn date(&self) -> String {
+        let (year, month, day) = civil_from_days((self.timestamp / 86_400) as i64);
+        format!("{year:04}-{month:02}-{day:02}")
+    }
+}
+
+/// Reasons a high-score file could not be read.
+#[derive(Debug)]
+pub enum HighScoreError {
+    Io(io::Error),
+    /// The file does not start with the expected header
+    MissingHeader,
+    /// The file was written by an incompatible version of the game
+    UnsupportedVersion(u32),
+    /// A row could not be parsed; holds the 1-based line number
+    Malformed(usize),
+}
+
+impl fmt::Display for HighScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HighScoreError::Io(error) => write!(f, "i/o error: {error}"),
+            HighScoreError::MissingHeader => write!(f, "missing file header"),
+            HighScoreError::UnsupportedVersion(version) => {
+                write!(f, "unsupported file version {version}")
+            }
+            HighScoreError::Malformed(line) => write!(f, "malformed entry on line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for HighScoreError {}
+
+impl From<io::Error> for HighScoreError {
+    fn from(error: io::Error) -> Self {
+        HighScoreError::Io(error)
+    }
+}
+
+/// The top scores, best first, along with the file they are persisted to.
+///
+/// Without a path the table lives in memory only.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct HighScores {
+    pub entries: Vec<HighScoreEntry>,
+    pub path: Option<PathBuf>,
+}
+
+impl HighScores {
+    /// Loads the table from the default location.
+    ///
+    /// Never fails: a missing file yields an empty table, and an unreadable or
+    /// corrupted one is logged, set aside as `.bak` and replaced by an empty table.
+    pub fn load_or_default() -> Self {
+        let Some(path) = default_path() else {
+            warn!("No data directory found, high scores will not be saved");
+            return Self::default();
+        };
+
+        let entries = match fs::read_to_string(&path) {
+            Ok(contents) => match parse(&contents) {
+                Ok(entries) => entries,
+                Err(error) => {
+                    warn!("Ignoring corrupted high-score file {}: {error}", path.display());
+                    let _ = fs::rename(&path, path.with_extension("bak"));
+                    Vec::new()
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(error) => {
+                warn!("Could not read high-score file {}: {error}", path.display());
+                Vec::new()
+            }
+        };
+
+        Self {
+            entries,
+            path: Some(path),
+        }
+    }
+
+    /// Returns true if `score` would earn a place in the table.
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.last().is_some_and(|lowest| score > lowest.score))
+    }
+
+    /// Inserts an entry in rank order, dropping whatever falls off the bottom.
+    ///
+    /// Returns the entry's 0-based rank, or `None` if it did not make the table.
+    pub fn insert(&mut self, entry: HighScoreEntry) -> Option<usize> {
+        if !self.qualifies(entry.score) {
+            return None;
+        }
+
+        // Ties go below existing entries, so the earlier score keeps its rank
+        let rank = self
+            .entries
+            .iter()
+            .position(|existing| entry.score > existing.score)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+
+    /// Writes the table to its file, if it has one.
+    ///
+    /// The file is written next to the target and renamed over it, so a crash
+    /// mid-write cannot leave a truncated table behind.
+    pub fn save(&self) -> Result<(), HighScoreError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, serialize(&self.entries))?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Returns the high-score file location under the user's data directory.
+pub fn default_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("asteroids_rust").join("highscores.txt"))
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    std::env::var_os("APPDATA").map(PathBuf::from)
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub(crate) fn data_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+}
+
+/// Parses the contents of a high-score file.
+///
+/// The first line is `asteroids-highscores v<version>`, followed by one
+/// tab-separated `score initials timestamp wave` row per entry.
+pub fn parse(contents: &str) -> Result<Vec<HighScoreEntry>, HighScoreError> {
+    let mut lines = contents.lines();
+
+    let header = lines.next().ok_or(HighScoreError::MissingHeader)?;
+    let version = header
+        .strip_prefix(FILE_HEADER)
+        .and_then(|rest| rest.trim().strip_prefix('v'))
+        .and_then(|version| version.parse::<u32>().ok())
+        .ok_or(HighScoreError::MissingHeader)?;
+    if version != FILE_VERSION {
+        return Err(HighScoreError::UnsupportedVersion(version));
+    }
+
+    let mut entries = Vec::new();
+    for (index, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let line_number = index + 2;
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [score, initials, timestamp, wave] = fields[..] else {
+            return Err(HighScoreError::Malformed(line_number));
+        };
+
+        let entry = HighScoreEntry {
+            score: score.parse().map_err(|_| HighScoreError::Malformed(line_number))?,
+            initials: initials.to_string(),
+            timestamp: timestamp.parse().map_err(|_| HighScoreError::Malformed(line_number))?,
+            wave: wave.parse().map_err(|_| HighScoreError::Malformed(line_number))?,
+        };
+        if entry.initials.is_empty() || entry.initials.len() > INITIALS_LENGTH {
+            return Err(HighScoreError::Malformed(line_number));
+        }
+        entries.push(entry);
+    }
+
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+    entries.truncate(MAX_HIGH_SCORES);
+    Ok(entries)
+}
+
+/// Serializes entries into the format read by [`parse`].
+pub fn serialize(entries: &[HighScoreEntry]) -> String {
+    let mut contents = format!("{FILE_HEADER} v{FILE_VERSION}\n");
+    for entry in entries {
+        contents.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            entry.score, entry.initials, entry.timestamp, entry.wave
+        ));
+    }
+    contents
+}
+
+/// Converts days since the Unix epoch into a `(year, month, day)` civil date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Initials being typed on the game over screen after a qualifying score.
+///
+/// Only present while the player is entering initials.
+#[derive(Resource, Debug, Default)]
+pub struct InitialsEntry {
+    pub letters: String,
+}
+
+/// Starts initials entry if the session's score earned a place in the table.
+pub fn begin_initials_entry(mut commands: Commands, score: Res<Score>, high_scores: Res<HighScores>) {
+    if high_scores.qualifies(score.best()) {
+        commands.init_resource::<InitialsEntry>();
+    }
+}
+
+/// Drops an unfinished initials entry when leaving the game over screen.
+pub fn end_initials_entry(mut commands: Commands) {
+    commands.remove_resource::<InitialsEntry>();
+}
+
+/// Types initials with the letter keys, Backspace to correct and Enter to confirm.
+///
+/// On a gamepad the D-pad does the typing: up and down step the last letter
+/// through the alphabet, right adds a letter and left removes one, and Start
+/// confirms. Confirming records the score in the table and saves it to disk.
+#[allow(clippy::too_many_arguments)]
+pub fn initials_input_system(
+    mut commands: Commands,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut gamepad_buttons: ResMut<ButtonInput<GamepadButton>>,
+    active_gamepads: Res<ActiveGamepads>,
+    mut entry: ResMut<InitialsEntry>,
+    mut high_scores: ResMut<HighScores>,
+    score: Res<Score>,
+    wave: Res<Wave>,
+) {
+    for key in keyboard_input.get_just_pressed() {
+        if let Some(letter) = key_to_letter(*key) {
+            if entry.letters.len() < INITIALS_LENGTH {
+                entry.letters.push(letter);
+            }
+        } else if *key == KeyCode::Backspace {
+            entry.letters.pop();
+        }
+    }
+
+    let pad = |button_type| gamepad::just_pressed(&active_gamepads, &gamepad_buttons, button_type);
+    if pad(GamepadButtonType::DPadRight) && entry.letters.len() < INITIALS_LENGTH {
+        entry.letters.push('A');
+    }
+    if pad(GamepadButtonType::DPadLeft) {
+        entry.letters.pop();
+    }
+    for (button_type, step) in [(GamepadButtonType::DPadUp, 1), (GamepadButtonType::DPadDown, -1)] {
+        if pad(button_type) {
+            // With nothing typed yet, the first letter starts out as an A
+            let letter = entry.letters.pop().map_or('A', |letter| step_letter(letter, step));
+            entry.letters.push(letter);
+        }
+    }
+
+    let confirmed = keyboard_input.just_pressed(KeyCode::Enter) || pad(gamepad::START_BUTTON);
+    if !confirmed || entry.letters.is_empty() {
+        return;
+    }
+    // Consume the confirmation so it does not also dismiss the game over screen this frame
+    keyboard_input.clear_just_pressed(KeyCode::Enter);
+    for (_, gamepad) in active_gamepads.iter() {
+        gamepad_buttons.clear_just_pressed(GamepadButton::new(gamepad, gamepad::START_BUTTON));
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    high_scores.insert(HighScoreEntry {
+        score: score.best(),
+        initials: entry.letters.clone(),
+        timestamp,
+        wave: wave.number,
+    });
+    if let Err(error) = high_scores.save() {
+        warn!("Could not save high scores: {error}");
+    }
+
+    commands.remove_resource::<InitialsEntry>();
+}
+
+/// Moves an uppercase letter `step` places through the alphabet, wrapping from Z to A.
+fn step_letter(letter: char, step: i8) -> char {
+    let index = (letter as u8 - b'A') as i8 + step;
+    (index.rem_euclid(26) as u8 + b'A') as char
+}
+
+/// Maps the letter keys to their uppercase character.
+fn key_to_letter(key: KeyCode) -> Option<char> {
+    const LETTERS: [(KeyCode, char); 26] = [
+        (KeyCode::KeyA, 'A'),
+        (KeyCode::KeyB, 'B'),
+        (KeyCode::KeyC, 'C'),
+        (KeyCode::KeyD, 'D'),
+        (KeyCode::KeyE, 'E'),
+        (KeyCode::KeyF, 'F'),
+        (KeyCode::KeyG, 'G'),
+        (KeyCode::KeyH, 'H'),
+        (KeyCode::KeyI, 'I'),
+        (KeyCode::KeyJ, 'J'),
+        (KeyCode::KeyK, 'K'),
+        (KeyCode::KeyL, 'L'),
+        (KeyCode::KeyM, 'M'),
+        (KeyCode::KeyN, 'N'),
+        (KeyCode::KeyO, 'O'),
+        (KeyCode::KeyP, 'P'),
+        (KeyCode::KeyQ, 'Q'),
+        (KeyCode::KeyR, 'R'),
+        (KeyCode::KeyS, 'S'),
+        (KeyCode::KeyT, 'T'),
+        (KeyCode::KeyU, 'U'),
+        (KeyCode::KeyV, 'V'),
+        (KeyCode::KeyW, 'W'),
+        (KeyCode::KeyX, 'X'),
+        (KeyCode::KeyY, 'Y'),
+        (KeyCode::KeyZ, 'Z'),
+    ];
+
+    LETTERS
+        .iter()
+        .find(|(candidate, _)| *candidate == key)
+        .map(|(_, letter)| *letter)
+}
@@ -0,0 +1,149 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::bounds::WorldBounds;
+use crate::controls::{ControlFrame, PlayerControls};
+use crate::particles;
+use crate::physics::{PhysicalTranslation, PreviousPhysicalTranslation};
+use crate::player::{self, Lives, Player, MAX_PLAYERS};
+use crate::rng::GameRng;
+
+/// Tuning for the hyperspace jump.
+#[derive(Resource, Debug, Clone)]
+pub struct HyperspaceConfig {
+    /// Seconds before the jump can be used again
+    pub cooldown_seconds: f32,
+    /// Probability in `0.0..=1.0` that the ship blows up on arrival
+    pub self_destruct_chance: f32,
+    /// Minimum distance kept between the destination and the screen edges
+    pub edge_margin: f32,
+}
+
+impl Default for HyperspaceConfig {
+    fn default() -> Self {
+        Self {
+            cooldown_seconds: 3.0,
+            self_destruct_chance: 0.1,
+            edge_margin: 60.0,
+        }
+    }
+}
+
+/// Time left before each player's next hyperspace jump is allowed.
+#[derive(Resource)]
+pub struct HyperspaceCooldown {
+    /// Indexed by `Player::id`
+    pub timers: [Timer; MAX_PLAYERS],
+}
+
+impl Default for HyperspaceCooldown {
+    fn default() -> Self {
+        let timers = std::array::from_fn(|_| {
+            let mut timer = Timer::from_seconds(0.0, TimerMode::Once);
+            // Start ready so the first jump is available immediately
+            timer.tick(timer.duration());
+            timer
+        });
+        Self { timers }
+    }
+}
+
+/// Teleports a ship to a random point on the tick its player requests a hyperspace jump.
+///
+/// This system:
+/// 1. Checks the cooldown and starts a new one
+/// 2. Leaves a particle burst at the departure point
+/// 3. Moves the ship's avian2d position, writing both the current and previous
+///    physical translation so interpolation does not draw a streak across the screen
+/// 4. Leaves a second burst at the arrival point
+/// 5. Rolls the self-destruct chance and destroys the ship on a hit
+#[allow(clippy::too_many_arguments)]
+pub fn hyperspace_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    controls: Res<PlayerControls>,
+    config: Res<HyperspaceConfig>,
+    bounds: Res<WorldBounds>,
+    mut cooldown: ResMut<HyperspaceCooldown>,
+    mut lives: ResMut<Lives>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+    mut query: Query<
+        (
+            Entity,
+            &Player,
+            &mut Position,
+            &mut PhysicalTranslation,
+            &mut PreviousPhysicalTranslation,
+        ),
+    >,
+) {
+    for timer in cooldown.timers.iter_mut() {
+        timer.tick(time.delta());
+    }
+
+    // Ships jump in id order so the gameplay stream is drawn in the same order every run
+    let mut ships: Vec<_> = query.iter_mut().collect();
+    ships.sort_by_key(|(_, player, ..)| player.id);
+
+    for (ship_entity, player, mut position, mut translation, mut prev_translation) in ships {
+        let timer = &mut cooldown.timers[player.id];
+        if !controls.get(player.id).pressed(ControlFrame::HYPERSPACE) || !timer.finished() {
+            continue;
+        }
+        *timer = Timer::from_seconds(config.cooldown_seconds, TimerMode::Once);
+
+        let half_width = (bounds.half_extents.x - config.edge_margin).max(0.0);
+        let half_height = (bounds.half_extents.y - config.edge_margin).max(0.0);
+        let departure = translation.0.truncate();
+        let arrival = Vec2::new(
+            rng.gameplay.gen_range(-half_width..=half_width),
+            rng.gameplay.gen_range(-half_height..=half_height),
+        );
+
+        let burst_color = Color::srgb(0.5, 0.9, 1.0);
+        particles::spawn_explosion_particles(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            departure,
+            20,
+            burst_color,
+            &mut rng.cosmetic,
+        );
+
+        position.0 = arrival;
+        translation.0 = arrival.extend(translation.0.z);
+        prev_translation.0 = translation.0;
+
+        particles::spawn_explosion_particles(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            arrival,
+            20,
+            burst_color,
+            &mut rng.cosmetic,
+        );
+
+        if rng.gameplay.gen_bool(config.self_destruct_chance.clamp(0.0, 1.0) as f64) {
+            player::destroy_ship(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                ship_entity,
+                *player,
+                arrival,
+                &mut lives,
+                &mut rng.cosmetic,
+            );
+        }
+    }
+}
+
+/// Makes the jump available immediately at the start of a session.
+pub fn reset_hyperspace(mut cooldown: ResMut<HyperspaceCooldown>) {
+    *cooldown = HyperspaceCooldown::default();
+}
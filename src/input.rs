@@ -0,0 +1,677 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::gamepad::Deadzones;
+use crate::highscores;
+use crate::player::MAX_PLAYERS;
+use crate::state::GameState;
+
+/// First line of every bindings file, followed by the format version
+const FILE_HEADER: &str = "asteroids-bindings";
+
+/// Format version written by this build
+const FILE_VERSION: u32 = 1;
+
+/// Written in place of a key for a binding that has none
+pub const UNBOUND: &str = "Unbound";
+
+/// Keys that drive the menus and can never be bound to an action.
+///
+/// Enter confirms on every screen, Backspace cancels a rebind and Q quits a
+/// paused session.
+pub const RESERVED_KEYS: [KeyCode; 3] = [KeyCode::Enter, KeyCode::Backspace, KeyCode::KeyQ];
+
+/// Something the player can do, independent of the key that triggers it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Thrust,
+    Reverse,
+    RotateLeft,
+    RotateRight,
+    Fire,
+    SwitchWeapon,
+    Hyperspace,
+    ResetShip,
+    Pause,
+    DebugWireframe,
+}
+
+impl Action {
+    /// Every action, in the order they are listed on the rebinding screen
+    pub const ALL: [Action; 10] = [
+        Action::Thrust,
+        Action::Reverse,
+        Action::RotateLeft,
+        Action::RotateRight,
+        Action::Fire,
+        Action::SwitchWeapon,
+        Action::Hyperspace,
+        Action::ResetShip,
+        Action::Pause,
+        Action::DebugWireframe,
+    ];
+
+    /// Identifier used for the action in the bindings file.
+    pub fn id(self) -> &'static str {
+        match self {
+            Action::Thrust => "thrust",
+            Action::Reverse => "reverse",
+            Action::RotateLeft => "rotate_left",
+            Action::RotateRight => "rotate_right",
+            Action::Fire => "fire",
+            Action::SwitchWeapon => "switch_weapon",
+            Action::Hyperspace => "hyperspace",
+            Action::ResetShip => "reset_ship",
+            Action::Pause => "pause",
+            Action::DebugWireframe => "debug_wireframe",
+        }
+    }
+
+    /// Human-readable name shown on the rebinding screen.
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::Thrust => "Thrust",
+            Action::Reverse => "Reverse",
+            Action::RotateLeft => "Rotate left",
+            Action::RotateRight => "Rotate right",
+            Action::Fire => "Fire",
+            Action::SwitchWeapon => "Switch weapon",
+            Action::Hyperspace => "Hyperspace",
+            Action::ResetShip => "Reset ship",
+            Action::Pause => "Pause",
+            Action::DebugWireframe => "Toggle wireframe",
+        }
+    }
+
+    /// Looks an action up by its file identifier.
+    pub fn from_id(id: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|action| action.id() == id)
+    }
+
+    /// Returns true for actions that belong to the whole game rather than one ship.
+    ///
+    /// Shared actions have a single binding, stored as player one's.
+    pub fn is_shared(self) -> bool {
+        matches!(self, Action::Pause | Action::DebugWireframe)
+    }
+
+    /// Key player one's action is bound to out of the box.
+    ///
+    /// A saved file that gives this key to another action keeps it there, so
+    /// a new action's default can leave the action unbound for older files.
+    pub fn default_key(self) -> KeyCode {
+        match self {
+            Action::Thrust => KeyCode::KeyW,
+            Action::Reverse => KeyCode::KeyS,
+            Action::RotateLeft => KeyCode::KeyA,
+            Action::RotateRight => KeyCode::KeyD,
+            Action::Fire => KeyCode::Space,
+            Action::SwitchWeapon => KeyCode::KeyE,
+            Action::Hyperspace => KeyCode::ShiftLeft,
+            Action::ResetShip => KeyCode::KeyR,
+            Action::Pause => KeyCode::Escape,
+            Action::DebugWireframe => KeyCode::Tab,
+        }
+    }
+}
+
+/// One action of one player, the unit a key is bound to.
+///
+/// Shared actions always belong to player one, so there is exactly one
+/// binding for each of them however it was built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Binding {
+    player: usize,
+    action: Action,
+}
+
+impl Binding {
+    /// The binding of `player`'s `action`.
+    pub fn new(player: usize, action: Action) -> Self {
+        let player = if action.is_shared() { 0 } else { player };
+        Self { player, action }
+    }
+
+    /// Every binding, in the order they are listed on the rebinding screen.
+    pub fn all() -> Vec<Binding> {
+        (0..MAX_PLAYERS)
+            .flat_map(|player| Action::ALL.map(|action| (player, action)))
+            .filter(|(player, action)| *player == 0 || !action.is_shared())
+            .map(|(player, action)| Binding::new(player, action))
+            .collect()
+    }
+
+    pub fn player(self) -> usize {
+        self.player
+    }
+
+    pub fn action(self) -> Action {
+        self.action
+    }
+
+    /// Identifier used for the binding in the bindings file.
+    ///
+    /// Player one's bindings use the bare action id, so files written before
+    /// two-player mode still load.
+    pub fn id(self) -> String {
+        match self.player {
+            0 => self.action.id().to_string(),
+            player => format!("p{}.{}", player + 1, self.action.id()),
+        }
+    }
+
+    /// Looks a binding up by its file identifier.
+    pub fn from_id(id: &str) -> Option<Binding> {
+        Binding::all().into_iter().find(|binding| binding.id() == id)
+    }
+
+    /// Human-readable name shown on the rebinding screen.
+    pub fn label(self) -> String {
+        if self.action.is_shared() {
+            self.action.label().to_string()
+        } else {
+            format!("P{} {}", self.player + 1, self.action.label())
+        }
+    }
+
+    /// Key the binding has out of the box.
+    ///
+    /// Player one flies with the left hand on WASD, player two with the arrow
+    /// keys and the right-hand modifiers.
+    pub fn default_key(self) -> KeyCode {
+        match (self.player, self.action) {
+            (0, action) => action.default_key(),
+            (_, Action::Thrust) => KeyCode::ArrowUp,
+            (_, Action::Reverse) => KeyCode::ArrowDown,
+            (_, Action::RotateLeft) => KeyCode::ArrowLeft,
+            (_, Action::RotateRight) => KeyCode::ArrowRight,
+            (_, Action::Fire) => KeyCode::ControlRight,
+            (_, Action::SwitchWeapon) => KeyCode::Comma,
+            (_, Action::Hyperspace) => KeyCode::ShiftRight,
+            (_, Action::ResetShip) => KeyCode::Period,
+            (_, action) => action.default_key(),
+        }
+    }
+}
+
+/// Player one's binding for `action`.
+impl From<Action> for Binding {
+    fn from(action: Action) -> Self {
+        Binding::new(0, action)
+    }
+}
+
+/// Reasons a set of bindings could not be read or applied.
+#[derive(Debug)]
+pub enum BindingsError {
+    Io(io::Error),
+    /// The file does not start with the expected header
+    MissingHeader,
+    /// The file was written by an incompatible version of the game
+    UnsupportedVersion(u32),
+    /// A line could not be parsed; holds the 1-based line number
+    Malformed(usize),
+    /// Two bindings share a key
+    Conflict(Binding, Binding, KeyCode),
+    /// A binding uses one of the `RESERVED_KEYS`
+    Reserved(Binding, KeyCode),
+}
+
+impl fmt::Display for BindingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingsError::Io(error) => write!(f, "i/o error: {error}"),
+            BindingsError::MissingHeader => write!(f, "missing file header"),
+            BindingsError::UnsupportedVersion(version) => {
+                write!(f, "unsupported file version {version}")
+            }
+            BindingsError::Malformed(line) => write!(f, "malformed binding on line {line}"),
+            BindingsError::Conflict(first, second, key) => write!(
+                f,
+                "{} is bound to both {} and {}",
+                key_name(*key),
+                first.label(),
+                second.label()
+            ),
+            BindingsError::Reserved(binding, key) => write!(
+                f,
+                "{} is reserved and cannot be bound to {}",
+                key_name(*key),
+                binding.label()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BindingsError {}
+
+impl From<io::Error> for BindingsError {
+    fn from(error: io::Error) -> Self {
+        BindingsError::Io(error)
+    }
+}
+
+/// Which key triggers each player's actions, along with the file it is persisted to.
+///
+/// Every binding has at most one key, and no two bindings share one, so both
+/// players fit on one keyboard. A binding without a key is unbound and never
+/// pressed. The gamepad deadzones are kept in the
+/// same file. Without a path the bindings live in memory only.
+#[derive(Resource, Debug, Clone)]
+pub struct InputBindings {
+    keys: HashMap<Binding, KeyCode>,
+    pub deadzones: Deadzones,
+    pub path: Option<PathBuf>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            keys: Binding::all()
+                .into_iter()
+                .map(|binding| (binding, binding.default_key()))
+                .collect(),
+            deadzones: Deadzones::default(),
+            path: None,
+        }
+    }
+}
+
+impl InputBindings {
+    /// Loads the bindings from the default location.
+    ///
+    /// Never fails: a missing file yields the default bindings, and an
+    /// unreadable, corrupted or conflicting one is logged and ignored.
+    pub fn load_or_default() -> Self {
+        let Some(path) = default_path() else {
+            warn!("No data directory found, key bindings will not be saved");
+            return Self::default();
+        };
+
+        let mut bindings = match fs::read_to_string(&path) {
+            Ok(contents) => parse(&contents).unwrap_or_else(|error| {
+                warn!("Ignoring key bindings file {}: {error}", path.display());
+                Self::default()
+            }),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(error) => {
+                warn!("Could not read key bindings file {}: {error}", path.display());
+                Self::default()
+            }
+        };
+        bindings.path = Some(path);
+        bindings
+    }
+
+    /// Key currently bound to `binding`, or to player one's action, if it has one.
+    pub fn key(&self, binding: impl Into<Binding>) -> Option<KeyCode> {
+        self.keys.get(&binding.into()).copied()
+    }
+
+    /// Binding that uses `key`, if any.
+    pub fn binding_for(&self, key: KeyCode) -> Option<Binding> {
+        Binding::all().into_iter().find(|binding| self.key(*binding) == Some(key))
+    }
+
+    /// Returns true while the binding's key is held down.
+    pub fn pressed(&self, binding: impl Into<Binding>, keyboard_input: &ButtonInput<KeyCode>) -> bool {
+        self.key(binding).is_some_and(|key| keyboard_input.pressed(key))
+    }
+
+    /// Returns true on the frame the binding's key goes down.
+    pub fn just_pressed(
+        &self,
+        binding: impl Into<Binding>,
+        keyboard_input: &ButtonInput<KeyCode>,
+    ) -> bool {
+        self.key(binding).is_some_and(|key| keyboard_input.just_pressed(key))
+    }
+
+    /// Binds `binding` to `key`.
+    ///
+    /// Fails without changing anything if the key is reserved or already
+    /// used by another binding, including the other player's.
+    pub fn bind(&mut self, binding: impl Into<Binding>, key: KeyCode) -> Result<(), BindingsError> {
+        let binding = binding.into();
+        if RESERVED_KEYS.contains(&key) {
+            return Err(BindingsError::Reserved(binding, key));
+        }
+        if let Some(other) = self.binding_for(key).filter(|other| *other != binding) {
+            return Err(BindingsError::Conflict(other, binding, key));
+        }
+
+        self.keys.insert(binding, key);
+        Ok(())
+    }
+
+    /// Takes the key away from `binding`, leaving it unbound.
+    pub fn unbind(&mut self, binding: impl Into<Binding>) {
+        self.keys.remove(&binding.into());
+    }
+
+    /// Finds the first pair of bindings sharing a key or a binding on a reserved key.
+    pub fn check(&self) -> Result<(), BindingsError> {
+        let bindings = Binding::all();
+        for (index, binding) in bindings.iter().enumerate() {
+            let Some(key) = self.key(*binding) else {
+                continue;
+            };
+            if RESERVED_KEYS.contains(&key) {
+                return Err(BindingsError::Reserved(*binding, key));
+            }
+            if let Some(other) = bindings[index + 1..]
+                .iter()
+                .find(|other| self.key(**other) == Some(key))
+            {
+                return Err(BindingsError::Conflict(*binding, *other, key));
+            }
+        }
+        Ok(())
+    }
+
+    /// Puts every binding back on its default key, keeping the deadzones and file path.
+    pub fn reset(&mut self) {
+        let path = self.path.take();
+        *self = Self {
+            deadzones: self.deadzones,
+            path,
+            ..default()
+        };
+    }
+
+    /// Writes the bindings to their file, if they have one.
+    pub fn save(&self) -> Result<(), BindingsError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, serialize(self))?;
+        fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Returns the bindings file location under the user's data directory.
+pub fn default_path() -> Option<PathBuf> {
+    highscores::data_dir().map(|dir| dir.join("asteroids_rust").join("bindings.txt"))
+}
+
+/// Parses the contents of a bindings file.
+///
+/// The first line is `asteroids-bindings v<version>`, followed by one
+/// `action = Key` line per binding, with player two's actions written as
+/// `p2.action = Key`, and optional `stick_deadzone = 0.15` and
+/// `trigger_deadzone = 0.1` lines. Blank lines and `#` comments are skipped,
+/// and anything missing from the file keeps its default.
+///
+/// A key the file gives to one binding is taken away from any binding still
+/// on its default, leaving that one unbound. Files written before a default
+/// existed, such as player one flying with the arrow keys before player two
+/// took them, keep loading as they were saved.
+pub fn parse(contents: &str) -> Result<InputBindings, BindingsError> {
+    let mut lines = contents.lines();
+
+    let header = lines.next().ok_or(BindingsError::MissingHeader)?;
+    let version = header
+        .strip_prefix(FILE_HEADER)
+        .and_then(|rest| rest.trim().strip_prefix('v'))
+        .and_then(|version| version.parse::<u32>().ok())
+        .ok_or(BindingsError::MissingHeader)?;
+    if version != FILE_VERSION {
+        return Err(BindingsError::UnsupportedVersion(version));
+    }
+
+    let mut bindings = InputBindings::default();
+    // Bindings the file set, which are never displaced by a later line
+    let mut from_file: Vec<Binding> = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // Line numbers are 1-based and the header is line 1
+        let line_number = index + 2;
+        let (name, value) = line
+            .split_once('=')
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .ok_or(BindingsError::Malformed(line_number))?;
+        match name {
+            "stick_deadzone" => {
+                bindings.deadzones.stick =
+                    parse_deadzone(value).ok_or(BindingsError::Malformed(line_number))?;
+            }
+            "trigger_deadzone" => {
+                bindings.deadzones.trigger =
+                    parse_deadzone(value).ok_or(BindingsError::Malformed(line_number))?;
+            }
+            _ => {
+                let binding = Binding::from_id(name).ok_or(BindingsError::Malformed(line_number))?;
+                from_file.push(binding);
+                if value.eq_ignore_ascii_case(UNBOUND) {
+                    bindings.unbind(binding);
+                    continue;
+                }
+                let key = parse_key(value).ok_or(BindingsError::Malformed(line_number))?;
+                if let Some(default) = bindings
+                    .binding_for(key)
+                    .filter(|other| *other != binding && !from_file.contains(other))
+                {
+                    bindings.unbind(default);
+                }
+                bindings.keys.insert(binding, key);
+            }
+        }
+    }
+
+    bindings.check()?;
+    Ok(bindings)
+}
+
+/// Serializes bindings into the format read by `parse`.
+pub fn serialize(bindings: &InputBindings) -> String {
+    let mut contents = format!("{FILE_HEADER} v{FILE_VERSION}\n");
+    for binding in Binding::all() {
+        let key = bound_key_name(bindings.key(binding));
+        contents.push_str(&format!("{} = {key}\n", binding.id()));
+    }
+    contents.push_str(&format!("stick_deadzone = {}\n", bindings.deadzones.stick));
+    contents.push_str(&format!("trigger_deadzone = {}\n", bindings.deadzones.trigger));
+    contents
+}
+
+/// Reads a deadzone, which must leave some of the axis' travel usable.
+fn parse_deadzone(value: &str) -> Option<f32> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|deadzone| (0.0..1.0).contains(deadzone))
+}
+
+/// Keys that can be bound, with the names used for them in files and on screen
+const KEY_NAMES: &[(KeyCode, &str)] = &[
+    (KeyCode::KeyA, "A"),
+    (KeyCode::KeyB, "B"),
+    (KeyCode::KeyC, "C"),
+    (KeyCode::KeyD, "D"),
+    (KeyCode::KeyE, "E"),
+    (KeyCode::KeyF, "F"),
+    (KeyCode::KeyG, "G"),
+    (KeyCode::KeyH, "H"),
+    (KeyCode::KeyI, "I"),
+    (KeyCode::KeyJ, "J"),
+    (KeyCode::KeyK, "K"),
+    (KeyCode::KeyL, "L"),
+    (KeyCode::KeyM, "M"),
+    (KeyCode::KeyN, "N"),
+    (KeyCode::KeyO, "O"),
+    (KeyCode::KeyP, "P"),
+    (KeyCode::KeyQ, "Q"),
+    (KeyCode::KeyR, "R"),
+    (KeyCode::KeyS, "S"),
+    (KeyCode::KeyT, "T"),
+    (KeyCode::KeyU, "U"),
+    (KeyCode::KeyV, "V"),
+    (KeyCode::KeyW, "W"),
+    (KeyCode::KeyX, "X"),
+    (KeyCode::KeyY, "Y"),
+    (KeyCode::KeyZ, "Z"),
+    (KeyCode::Digit0, "0"),
+    (KeyCode::Digit1, "1"),
+    (KeyCode::Digit2, "2"),
+    (KeyCode::Digit3, "3"),
+    (KeyCode::Digit4, "4"),
+    (KeyCode::Digit5, "5"),
+    (KeyCode::Digit6, "6"),
+    (KeyCode::Digit7, "7"),
+    (KeyCode::Digit8, "8"),
+    (KeyCode::Digit9, "9"),
+    (KeyCode::ArrowUp, "Up"),
+    (KeyCode::ArrowDown, "Down"),
+    (KeyCode::ArrowLeft, "Left"),
+    (KeyCode::ArrowRight, "Right"),
+    (KeyCode::Space, "Space"),
+    (KeyCode::Tab, "Tab"),
+    (KeyCode::Enter, "Enter"),
+    (KeyCode::Backspace, "Backspace"),
+    (KeyCode::Escape, "Escape"),
+    (KeyCode::ShiftLeft, "LeftShift"),
+    (KeyCode::ShiftRight, "RightShift"),
+    (KeyCode::ControlLeft, "LeftControl"),
+    (KeyCode::ControlRight, "RightControl"),
+    (KeyCode::AltLeft, "LeftAlt"),
+    (KeyCode::AltRight, "RightAlt"),
+    (KeyCode::Comma, "Comma"),
+    (KeyCode::Period, "Period"),
+    (KeyCode::Slash, "Slash"),
+    (KeyCode::Semicolon, "Semicolon"),
+    (KeyCode::Quote, "Quote"),
+    (KeyCode::BracketLeft, "LeftBracket"),
+    (KeyCode::BracketRight, "RightBracket"),
+    (KeyCode::Minus, "Minus"),
+    (KeyCode::Equal, "Equal"),
+    (KeyCode::Numpad0, "Numpad0"),
+    (KeyCode::Numpad1, "Numpad1"),
+    (KeyCode::Numpad2, "Numpad2"),
+    (KeyCode::Numpad3, "Numpad3"),
+    (KeyCode::Numpad4, "Numpad4"),
+    (KeyCode::Numpad5, "Numpad5"),
+    (KeyCode::Numpad6, "Numpad6"),
+    (KeyCode::Numpad7, "Numpad7"),
+    (KeyCode::Numpad8, "Numpad8"),
+    (KeyCode::Numpad9, "Numpad9"),
+];
+
+/// Name of a key as written in the bindings file and shown on screen.
+pub fn key_name(key: KeyCode) -> &'static str {
+    KEY_NAMES
+        .iter()
+        .find(|(code, _)| *code == key)
+        .map_or("Unknown", |(_, name)| *name)
+}
+
+/// Name of a binding's key, or `UNBOUND` for a binding without one.
+pub fn bound_key_name(key: Option<KeyCode>) -> &'static str {
+    key.map_or(UNBOUND, key_name)
+}
+
+/// Looks a key up by the name `key_name` gives it, ignoring case.
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, key_name)| key_name.eq_ignore_ascii_case(name))
+        .map(|(code, _)| *code)
+}
+
+/// Returns true if `key` can be bound to an action.
+pub fn is_bindable(key: KeyCode) -> bool {
+    !RESERVED_KEYS.contains(&key) && KEY_NAMES.iter().any(|(code, _)| *code == key)
+}
+
+/// Where the player is on the rebinding screen.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct Rebinding {
+    /// Index into `Binding::all()` of the highlighted binding
+    pub selected: usize,
+    /// True while the next key press becomes the highlighted action's binding
+    pub waiting: bool,
+    /// Feedback from the last attempt, such as a conflict
+    pub message: String,
+}
+
+/// Opens the rebinding screen.
+pub fn begin_rebinding(mut commands: Commands) {
+    commands.init_resource::<Rebinding>();
+}
+
+/// Drives the rebinding screen.
+///
+/// Up and Down move the highlight, Enter waits for a new key for the
+/// highlighted binding, Delete restores the defaults and Escape goes back to
+/// the main menu. While waiting, Backspace cancels and any other bindable key
+/// is assigned, unless another binding already uses it.
+pub fn rebinding_input_system(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut bindings: ResMut<InputBindings>,
+    mut rebinding: ResMut<Rebinding>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let all = Binding::all();
+
+    if rebinding.waiting {
+        let Some(key) = keyboard_input.get_just_pressed().next().copied() else {
+            return;
+        };
+        let binding = all[rebinding.selected];
+
+        if key == KeyCode::Backspace {
+            rebinding.waiting = false;
+            rebinding.message = String::new();
+        } else if RESERVED_KEYS.contains(&key) || is_bindable(key) {
+            rebinding.waiting = false;
+            rebinding.message = match bindings.bind(binding, key) {
+                Ok(()) => format!("{} bound to {}", binding.label(), key_name(key)),
+                Err(error) => error.to_string(),
+            };
+        }
+        return;
+    }
+
+    if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        rebinding.selected = (rebinding.selected + all.len() - 1) % all.len();
+    }
+    if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        rebinding.selected = (rebinding.selected + 1) % all.len();
+    }
+    if keyboard_input.just_pressed(KeyCode::Enter) {
+        rebinding.waiting = true;
+        rebinding.message = format!(
+            "Press a key for {} || Backspace to cancel",
+            all[rebinding.selected].label()
+        );
+    }
+    if keyboard_input.just_pressed(KeyCode::Delete) {
+        bindings.reset();
+        rebinding.message = "Restored the default bindings".to_string();
+    }
+    if keyboard_input.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+/// Saves the bindings when the rebinding screen is closed.
+pub fn end_rebinding(mut commands: Commands, bindings: Res<InputBindings>) {
+    commands.remove_resource::<Rebinding>();
+
+    if let Err(error) = bindings.save() {
+        warn!("Could not save key bindings: {error}");
+    }
+}
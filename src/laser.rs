@@ -0,0 +1,271 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::asteroids::{self, Asteroid, AsteroidDestroyed, Breakup};
+use crate::bounds::WorldBounds;
+use crate::controls::{ControlFrame, PlayerControls};
+use crate::physics::{PhysicalRotation, PhysicalTranslation};
+use crate::player::Player;
+use crate::rng::GameRng;
+use crate::weapons::{Faction, Loadout, Projectile, Weapon};
+
+/// Energy a ship's pool holds when full
+pub const ENERGY_CAPACITY: f32 = 100.0;
+
+/// Energy a ship's pool recovers per second while its beam is off
+pub const ENERGY_RECHARGE: f32 = 20.0;
+
+/// Share of the pool that has to come back before a drained beam restarts
+pub const ENERGY_RESTART_FRACTION: f32 = 0.25;
+
+/// A continuous beam: how far it reaches, what it costs and what it does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Beam {
+    /// Distance from the muzzle to the end of the beam when it hits nothing
+    pub range: f32,
+    pub width: f32,
+    /// Hit points taken off the asteroid in the beam's path every second
+    pub damage_per_second: f32,
+    /// Energy drained from the ship's pool every second the beam is on
+    pub energy_per_second: f32,
+    pub color: Color,
+}
+
+/// A ship's energy pool, drained by beam weapons.
+///
+/// Running the pool dry locks the beam out until `ENERGY_RESTART_FRACTION`
+/// of it has recharged, so an empty pool can't keep a flickering beam alive.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Energy {
+    pub current: f32,
+    pub capacity: f32,
+    /// True from running dry until enough has recharged to fire again
+    pub depleted: bool,
+}
+
+impl Default for Energy {
+    fn default() -> Self {
+        Self {
+            current: ENERGY_CAPACITY,
+            capacity: ENERGY_CAPACITY,
+            depleted: false,
+        }
+    }
+}
+
+impl Energy {
+    /// The pool's fill level, in `0.0..=1.0`
+    pub fn fraction(&self) -> f32 {
+        (self.current / self.capacity).clamp(0.0, 1.0)
+    }
+
+    /// Returns true if a beam can draw on the pool.
+    pub fn can_fire(&self) -> bool {
+        !self.depleted && self.current > 0.0
+    }
+
+    /// Takes `amount` out of the pool, locking it out if that empties it.
+    pub fn drain(&mut self, amount: f32) {
+        self.current = (self.current - amount).max(0.0);
+        if self.current == 0.0 {
+            self.depleted = true;
+        }
+    }
+
+    /// Puts `amount` back into the pool, lifting the lockout once enough is back.
+    pub fn recharge(&mut self, amount: f32) {
+        self.current = (self.current + amount).min(self.capacity);
+        if self.fraction() >= ENERGY_RESTART_FRACTION {
+            self.depleted = false;
+        }
+    }
+}
+
+/// A red beam that burns through whatever asteroid is in front of the ship.
+pub struct LaserBeam;
+
+impl Weapon for LaserBeam {
+    fn name(&self) -> &'static str {
+        "Laser"
+    }
+
+    fn fire_interval(&self) -> f32 {
+        0.0
+    }
+
+    fn projectile(&self) -> Option<Projectile> {
+        None
+    }
+
+    fn beam(&self) -> Option<Beam> {
+        Some(Beam {
+            range: 450.0,
+            width: 3.0,
+            damage_per_second: 4.0,
+            energy_per_second: 35.0,
+            color: Color::srgb(1.0, 0.25, 0.2),
+        })
+    }
+}
+
+/// The mesh drawing one ship's beam.
+///
+/// Spawned when the beam comes on and despawned as soon as it goes off.
+#[derive(Component)]
+pub struct BeamVisual {
+    pub ship: Entity,
+}
+
+/// Drives the beam of every ship holding a beam weapon.
+///
+/// While fire is held and the ship has energy left, the beam is raycast from
+/// the muzzle each tick. The first asteroid in its path soaks up damage, and
+/// every full hit point of it is struck like a bullet hit; the beam ends at
+/// the hit point. Being a ray rather than a projectile, it cannot skip past a
+/// small asteroid between two ticks.
+///
+/// The beam drains the ship's `Energy` while on and lets it recharge while
+/// off; an empty pool cuts the beam until part of it has refilled. The beam
+/// is cast in a straight line and does not follow the wrap seam.
+///
+/// Like bullets, the beam leaves from the ship's simulated pose, so what it
+/// hits never depends on frame timing.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn laser_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+    controls: Res<PlayerControls>,
+    bounds: Res<WorldBounds>,
+    spatial_query: SpatialQuery,
+    mut ships: Query<(
+        Entity,
+        &Player,
+        &PhysicalTranslation,
+        &PhysicalRotation,
+        &Loadout,
+        &mut Energy,
+    )>,
+    mut asteroids: Query<(
+        &mut Asteroid,
+        &Position,
+        &LinearVelocity,
+        &AngularVelocity,
+        &ColliderDensity,
+    )>,
+    mut visuals: Query<(Entity, &BeamVisual, &mut Transform)>,
+    mut rng: ResMut<GameRng>,
+    mut destroyed_events: EventWriter<AsteroidDestroyed>,
+) {
+    let delta = time.delta_seconds();
+    // Each firing ship's beam, from the muzzle to where it ends
+    let mut active: Vec<(Entity, Vec2, Vec2, Quat, Beam)> = Vec::new();
+
+    for (ship_entity, player, translation, rotation, loadout, mut energy) in ships.iter_mut() {
+        let weapon = loadout.current();
+        let firing = controls.get(player.id).pressed(ControlFrame::FIRE);
+        let Some(beam) = weapon.beam().filter(|_| firing && energy.can_fire()) else {
+            energy.recharge(ENERGY_RECHARGE * delta);
+            continue;
+        };
+        energy.drain(beam.energy_per_second * delta);
+
+        let rotation = Quat::from_rotation_z(rotation.0);
+        let Ok(direction) = Dir2::new((rotation * Vec3::Y).truncate()) else {
+            continue;
+        };
+        let muzzle = translation.0.truncate()
+            + (rotation * weapon.muzzle().extend(0.0)).truncate();
+
+        let hit = spatial_query.cast_ray_predicate(
+            muzzle,
+            direction,
+            beam.range,
+            true,
+            SpatialQueryFilter::from_excluded_entities([ship_entity]),
+            &|entity| asteroids.contains(entity),
+        );
+        let Some(hit) = hit else {
+            active.push((ship_entity, muzzle, muzzle + *direction * beam.range, rotation, beam));
+            continue;
+        };
+        let end = muzzle + *direction * hit.time_of_impact;
+        active.push((ship_entity, muzzle, end, rotation, beam));
+
+        let Ok((mut asteroid, position, velocity, angular_velocity, density)) =
+            asteroids.get_mut(hit.entity)
+        else {
+            continue;
+        };
+        // Two beams on one asteroid may already have destroyed it this tick
+        if asteroid.hit_points == 0 {
+            continue;
+        }
+        asteroid.beam_damage += beam.damage_per_second * delta;
+        while asteroid.beam_damage >= 1.0 {
+            asteroid.beam_damage -= 1.0;
+            let breakup = Breakup {
+                velocity: velocity.0,
+                angular_velocity: angular_velocity.0,
+                mass: density.0 * asteroid.size.mass(),
+                radius: asteroid.size.radius(),
+                impact_direction: *direction,
+                impact_offset: bounds.delta(position.0, end),
+            };
+            let destroyed = asteroids::strike_asteroid(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &mut destroyed_events,
+                hit.entity,
+                &mut asteroid,
+                position.0,
+                Faction::Player(player.id),
+                breakup,
+                &mut rng.cosmetic,
+            );
+            if destroyed {
+                break;
+            }
+        }
+    }
+
+    // Beams are drawn as a unit square stretched from the muzzle to the end
+    let place = |start: Vec2, end: Vec2, rotation: Quat, beam: &Beam| {
+        Transform::from_translation(((start + end) / 2.0).extend(-0.1))
+            .with_rotation(rotation)
+            .with_scale(Vec3::new(beam.width, start.distance(end), 1.0))
+    };
+    let mut drawn: Vec<Entity> = Vec::new();
+    for (visual_entity, visual, mut transform) in visuals.iter_mut() {
+        match active.iter().find(|(ship, ..)| *ship == visual.ship) {
+            Some((ship, start, end, rotation, beam)) => {
+                *transform = place(*start, *end, *rotation, beam);
+                drawn.push(*ship);
+            }
+            None => commands.entity(visual_entity).despawn(),
+        }
+    }
+    for (ship, start, end, rotation, beam) in active {
+        if drawn.contains(&ship) {
+            continue;
+        }
+        commands.spawn((
+            BeamVisual { ship },
+            ColorMesh2dBundle {
+                mesh: meshes.add(Rectangle::new(1.0, 1.0)).into(),
+                material: materials.add(beam.color),
+                transform: place(start, end, rotation, &beam),
+                ..default()
+            },
+        ));
+    }
+}
+
+/// Clears every beam when a session is torn down.
+pub fn despawn_beams(mut commands: Commands, visuals: Query<Entity, With<BeamVisual>>) {
+    for entity in visuals.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -4,9 +4,17 @@
 //! 
 //! This crate provides the core game functionality including:
 //! - Physics simulation for ship movement
-//! - Player controls and input handling
+//! - Player controls and input handling, from the keyboard or a gamepad
 //! - UI elements and wireframe toggling
 //! - Weapon systems
+//! - Scoring and the in-game HUD
+//! - Wave-based asteroid spawning, with rock, ice, metal and crystal asteroids
+//! - Enemy flying saucers
+//! - A persistent high-score table
+//! - Game flow between the menu, play, pause and game over screens
+//! - Seeded randomness so any session can be reproduced
+//! - A headless mode that steps the simulation without a window
+//! - Input recording and frame-exact replays
 //! 
 //! ## Game Structure
 //! 
@@ -15,18 +23,54 @@
 
 /// Asteroids module containing asteroid entities, spawning, and collision systems
 pub mod asteroids;
+/// Bounds module containing the toroidal world bounds and the wrapping systems
+pub mod bounds;
+/// Controls module containing the per-tick control frame gameplay systems read their input from
+pub mod controls;
+/// Gamepad module containing analog gamepad controls, deadzones and hot-plugging
+pub mod gamepad;
+/// Headless module containing the windowless, manually stepped simulation used by tests
+pub mod headless;
+/// High-score module containing the persistent score table and initials entry
+pub mod highscores;
+/// Hyperspace module containing the ship's emergency teleport ability
+pub mod hyperspace;
+/// Input module containing the remappable key bindings, their config file and the rebinding screen
+pub mod input;
+/// Laser module containing the beam weapon and the ships' energy pools
+pub mod laser;
+/// Loot module containing the pickups metal asteroids drop
+pub mod loot;
+/// Missiles module containing the homing missile launcher, target acquisition and blasts
+pub mod missiles;
 /// Particles module containing particle effects and explosion systems
 pub mod particles;
 /// Physics module containing movement, rotation, and collision components and systems
 pub mod physics;
 /// Player module containing player ship components and spawning systems
 pub mod player;
+/// Replay module containing input recording and frame-exact playback
+pub mod replay;
+/// RNG module containing the seeded random streams every spawner draws from
+pub mod rng;
+/// Safety module containing the checks that keep spawns off a collision course
+pub mod safety;
+/// Saucers module containing the enemy flying saucers and their weapons
+pub mod saucers;
+/// Score module containing the session score and the events that award points
+pub mod score;
+/// State module containing the game flow state machine and its transitions
+pub mod state;
 /// UI module containing text display and wireframe toggle functionality
 pub mod ui;
-/// Weapons module containing shooting mechanics and timer resources
+/// Waves module containing the wave table and the wave progression systems
+pub mod waves;
+/// Weapons module containing the `Weapon` trait, ship loadouts and projectiles
 pub mod weapons;
 
+use avian2d::prelude::*;
 use bevy::prelude::*;
+use state::GameState;
 
 /// Main plugin for the Asteroids game
 /// 
@@ -37,6 +81,8 @@ use bevy::prelude::*;
 /// - UI elements
 /// - Physics systems
 /// - Input handling
+/// - Game state transitions, with gameplay gated on `GameState::Playing`
+/// - The avian2d simulation, stepped in `FixedPostUpdate` for every body including the ship
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
@@ -46,35 +92,218 @@ impl Plugin for GamePlugin {
     /// 
     /// * `app` - The Bevy app to add systems and resources to
     fn build(&self, app: &mut App) {
-        app.insert_resource(weapons::ShootTimer(Timer::from_seconds(
+        // Player files are only read for resources that weren't inserted before the plugin
+        if !app.world().contains_resource::<highscores::HighScores>() {
+            app.insert_resource(highscores::HighScores::load_or_default());
+        }
+        if !app.world().contains_resource::<input::InputBindings>() {
+            app.insert_resource(input::InputBindings::load_or_default());
+        }
+
+        app.add_plugins(PhysicsPlugins::new(FixedPostUpdate))
+        // Space has no down
+        .insert_resource(Gravity(Vec2::ZERO))
+        // The ship's transform is interpolated for rendering, so transforms never feed back into physics
+        .insert_resource(SyncConfig {
+            transform_to_position: false,
+            ..default()
+        })
+        .insert_resource(weapons::ShootTimer(Timer::from_seconds(
             2.0,
             TimerMode::Repeating,
         )))
-        .insert_resource(weapons::ShootCooldown::default())
-        .insert_resource(asteroids::AsteroidSpawnTimer::default())
-        .insert_resource(asteroids::AsteroidCount::default())
-        .add_systems(Startup, (ui::spawn_text, player::spawn_player))
+        // Like `GameRng`, bounds inserted before the plugin win, so replays keep their recorded size
+        .init_resource::<bounds::WorldBounds>()
+        .insert_resource(waves::WaveTable::default())
+        .insert_resource(safety::SpawnSafety::default())
+        .insert_resource(waves::Wave::default())
+        .init_resource::<asteroids::AsteroidPopulation>()
+        .observe(asteroids::count_spawned_asteroid)
+        .observe(asteroids::count_despawned_asteroid)
+        .add_event::<asteroids::AsteroidDestroyed>()
+        .insert_resource(player::Lives::default())
+        // Replays insert their recorded mode before the plugin
+        .init_resource::<player::GameMode>()
+        .insert_resource(score::Score::default())
+        .add_event::<score::ScoreEvent>()
+        .insert_resource(hyperspace::HyperspaceConfig::default())
+        .insert_resource(hyperspace::HyperspaceCooldown::default())
+        .insert_resource(saucers::SaucerConfig::default())
+        .insert_resource(saucers::SaucerSpawnTimer::default())
+        // Keeps a seed inserted before the plugin, such as one from the command line
+        .init_resource::<rng::GameRng>()
+        .init_resource::<controls::PlayerControls>()
+        .init_resource::<controls::ControlLatch>()
+        .init_resource::<gamepad::ActiveGamepads>()
+        .init_state::<GameState>()
+        .add_systems(Startup, (
+            ui::spawn_text,
+            player::spawn_camera,
+            rng::log_seed,
+            replay::start_playback.run_if(resource_exists::<replay::Playback>),
+        ))
+        .add_systems(OnEnter(GameState::MainMenu), (
+            ui::spawn_main_menu,
+            ui::despawn_screen::<ui::HudUi>,
+            ui::despawn_screen::<ui::WaveBannerUi>,
+            waves::end_waves,
+            player::despawn_player,
+            weapons::despawn_bullets,
+            laser::despawn_beams,
+            asteroids::despawn_asteroids,
+            loot::despawn_loot,
+            saucers::despawn_saucers,
+            particles::despawn_particles,
+            replay::end_playback,
+        ))
+        .add_systems(OnExit(GameState::MainMenu), (
+            ui::despawn_screen::<ui::MainMenuUi>,
+            ui::spawn_hud,
+            replay::begin_recording
+                .before(rng::start_session)
+                .run_if(resource_exists::<replay::Recording>),
+            rng::start_session,
+            controls::reset_controls,
+            score::reset_score,
+            waves::start_waves,
+            hyperspace::reset_hyperspace,
+            saucers::reset_saucer_timer,
+            player::spawn_player,
+        ))
+        .add_systems(OnEnter(GameState::Playing), state::resume_physics)
+        .add_systems(OnEnter(GameState::Paused), (ui::spawn_pause_overlay, state::pause_physics))
+        .add_systems(OnExit(GameState::Paused), ui::despawn_screen::<ui::PauseUi>)
+        .add_systems(OnEnter(GameState::GameOver), (
+            ui::spawn_game_over_screen,
+            // Replayed scores are not new achievements
+            highscores::begin_initials_entry.run_if(not(resource_exists::<replay::Playback>)),
+            state::pause_physics,
+            replay::save_recording.run_if(resource_exists::<replay::Recording>),
+        ))
+        .add_systems(
+            OnTransition { exited: GameState::Paused, entered: GameState::MainMenu },
+            replay::save_recording.run_if(resource_exists::<replay::Recording>),
+        )
+        .add_systems(OnExit(GameState::GameOver), (
+            ui::despawn_screen::<ui::GameOverUi>,
+            highscores::end_initials_entry,
+        ))
+        .add_systems(OnEnter(GameState::Controls), input::begin_rebinding)
+        .add_systems(OnExit(GameState::Controls), (
+            input::end_rebinding,
+            ui::despawn_screen::<ui::ControlsUi>,
+        ))
+        .add_systems(Update, (
+            // Wireframes only exist when rendering, so headless apps skip the toggle
+            ui::toggle_wireframe
+                .run_if(resource_exists::<bevy::sprite::Wireframe2dConfig>)
+                .run_if(not(in_state(GameState::Controls))),
+            ui::update_hint_text.run_if(
+                resource_changed::<input::InputBindings>.or_else(resource_changed::<player::GameMode>),
+            ),
+            bounds::update_world_bounds.run_if(not(resource_exists::<replay::Playback>)),
+            (
+                state::start_game,
+                ui::update_menu_mode_text.run_if(resource_changed::<player::GameMode>),
+            )
+                .chain()
+                .run_if(in_state(GameState::MainMenu)),
+            state::open_controls.run_if(in_state(GameState::MainMenu)),
+            (
+                input::rebinding_input_system,
+                ui::update_controls_screen.run_if(
+                    resource_exists_and_changed::<input::Rebinding>
+                        .or_else(resource_changed::<input::InputBindings>),
+                ),
+            )
+                .chain()
+                .run_if(in_state(GameState::Controls)),
+            state::toggle_pause,
+            state::quit_to_menu.run_if(in_state(GameState::Paused)),
+            state::leave_game_over
+                .run_if(in_state(GameState::GameOver))
+                .run_if(not(resource_exists::<highscores::InitialsEntry>)),
+            highscores::initials_input_system
+                .run_if(resource_exists::<highscores::InitialsEntry>),
+            ui::update_initials_text.run_if(in_state(GameState::GameOver)),
+            ui::update_lives_text,
+            ui::update_score_text,
+            ui::update_wave_text,
+            ui::update_weapon_text,
+            ui::spawn_wave_banner.run_if(resource_added::<waves::WaveIntermission>),
+            ui::despawn_screen::<ui::WaveBannerUi>
+                .run_if(resource_removed::<waves::WaveIntermission>()),
+        ))
         .add_systems(Update, (
-            physics::reset_ship_position, 
-            physics::wrap_screen_position, 
-            ui::toggle_wireframe,
-            weapons::shoot_system,
+            bounds::wrap_transforms,
+            particles::update_particles,
+        ).run_if(in_state(GameState::Playing)))
+        // Every tick first settles on its controls, live or replayed, then recorded
+        .add_systems(FixedUpdate, (
+            controls::sample_controls,
+            replay::playback_controls.run_if(resource_exists::<replay::Playback>),
+            replay::record_controls.run_if(resource_exists::<replay::Recording>),
+        ).chain().run_if(in_state(GameState::Playing)))
+        // Gameplay runs on fixed ticks so a replay re-simulates it exactly.
+        // Chained so systems drawing from `GameRng` always do so in the same order.
+        .add_systems(FixedUpdate, (
+            physics::reset_ship_position,
+            hyperspace::hyperspace_system,
+            physics::gather_movement_input,
+            physics::apply_rotation_input,
+            physics::apply_movement,
+            (weapons::shoot_system, laser::laser_system, missiles::missile_guidance_system).chain(),
             weapons::bullet_lifetime_system,
-            asteroids::spawn_asteroid_system,
-            asteroids::wrap_asteroids,
+            waves::wave_system,
+            missiles::missile_detonation_system,
             asteroids::bullet_asteroid_collision_system,
-            particles::update_particles,
+            asteroids::break_up_asteroids,
+            loot::loot_lifetime_system,
+            loot::loot_pickup_system,
+            score::apply_score_events,
+            player::ship_asteroid_collision_system,
+            player::ship_bullet_collision_system
+                .run_if(resource_equals(player::GameMode::Versus)),
+            player::respawn_system,
+            player::invulnerability_system,
             particles::engine_particle_system,
-        ))
-        .add_systems(FixedUpdate, physics::update_physics_state)
+        ).chain().after(replay::record_controls).run_if(in_state(GameState::Playing)))
+        .add_systems(FixedUpdate, (
+            saucers::spawn_saucer_system,
+            saucers::saucer_movement_system,
+            saucers::saucer_fire_system,
+            saucers::saucer_collision_system,
+        ).chain().after(particles::engine_particle_system).run_if(in_state(GameState::Playing)))
+        .add_systems(
+            FixedPostUpdate,
+            (physics::update_physics_state, bounds::wrap_bodies)
+                .chain()
+                .after(PhysicsSet::Sync)
+                .run_if(in_state(GameState::Playing)),
+        )
         .add_systems(
             PreUpdate,
             (
-                physics::gather_movement_input,
-                physics::apply_movement,
-                physics::apply_rotation_input,
-            ),
+                gamepad::track_gamepads,
+                (controls::latch_keyboard_controls, gamepad::latch_gamepad_controls)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+                .chain()
+                .after(bevy::input::InputSystem),
+        )
+        .add_systems(
+            PostUpdate,
+            (
+                bounds::spawn_ghosts,
+                physics::interpolate_rendered_transform,
+                bounds::update_ghosts,
+            )
+                .chain(),
         )
-        .add_systems(PostUpdate, physics::interpolate_rendered_transform);
+        .add_systems(
+            Last,
+            replay::save_recording_on_exit.run_if(resource_exists::<replay::Recording>),
+        );
     }
 }
\ No newline at end of file
@@ -0,0 +1,142 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::bounds::{seam_contacts, BoundingRadius, ScreenWrap, WorldBounds};
+use crate::player::Player;
+use crate::score::ScoreEvent;
+
+/// Points a ship collects by flying through a loot pickup
+pub const LOOT_POINTS: u32 = 250;
+
+/// Seconds a pickup drifts around before it disappears
+pub const LOOT_LIFETIME: f32 = 8.0;
+
+/// Radius of a pickup's collider
+const LOOT_RADIUS: f32 = 8.0;
+
+/// A salvage pickup dropped by a destroyed metal asteroid.
+///
+/// Drifts slowly and is collected by whichever ship touches it first.
+#[derive(Component)]
+pub struct Loot {
+    pub lifetime: Timer,
+    pub points: u32,
+}
+
+impl Default for Loot {
+    fn default() -> Self {
+        Self {
+            lifetime: Timer::from_seconds(LOOT_LIFETIME, TimerMode::Once),
+            points: LOOT_POINTS,
+        }
+    }
+}
+
+/// Drops a pickup at `position`, drifting off in a random direction.
+///
+/// The drift is drawn from `rng`, which has to be the gameplay stream since
+/// it decides where the pickup can be collected.
+pub fn spawn_loot(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    position: Vec2,
+    rng: &mut impl Rng,
+) {
+    let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+    let velocity = Vec2::from_angle(angle) * rng.gen_range(10.0..30.0);
+
+    commands.spawn((
+        Loot::default(),
+        ColorMesh2dBundle {
+            mesh: meshes.add(Rhombus::new(LOOT_RADIUS * 2.0, LOOT_RADIUS * 2.0)).into(),
+            material: materials.add(Color::srgb(1.0, 0.8, 0.2)),
+            transform: Transform::from_translation(position.extend(0.0)),
+            ..default()
+        },
+        RigidBody::Kinematic,
+        Position(position),
+        // Pickups are collected on contact, they should never push the ship around
+        Sensor,
+        Collider::circle(LOOT_RADIUS),
+        BoundingRadius(LOOT_RADIUS),
+        LinearVelocity(velocity),
+        ScreenWrap,
+    ));
+}
+
+/// Removes pickups nobody collected in time.
+pub fn loot_lifetime_system(
+    mut commands: Commands,
+    mut loot: Query<(Entity, &mut Loot)>,
+    time: Res<Time>,
+) {
+    for (entity, mut loot) in loot.iter_mut() {
+        loot.lifetime.tick(time.delta());
+        if loot.lifetime.finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Hands a pickup's points to the player whose ship touches it.
+///
+/// Like the other collision systems, contacts across a wrap seam count too.
+pub fn loot_pickup_system(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionStarted>,
+    bounds: Res<WorldBounds>,
+    ships: Query<(Entity, &Player, &Position, &BoundingRadius)>,
+    pickups: Query<(Entity, &Loot, &Position, &BoundingRadius)>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    let mut touches: Vec<(Entity, Entity)> = collision_events
+        .read()
+        .filter_map(|CollisionStarted(entity1, entity2)| {
+            if ships.contains(*entity1) && pickups.contains(*entity2) {
+                Some((*entity1, *entity2))
+            } else if ships.contains(*entity2) && pickups.contains(*entity1) {
+                Some((*entity2, *entity1))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let ship_circles: Vec<_> = ships
+        .iter()
+        .map(|(entity, _, position, radius)| (entity, position.0, radius.0))
+        .collect();
+    let loot_circles: Vec<_> = pickups
+        .iter()
+        .map(|(entity, _, position, radius)| (entity, position.0, radius.0))
+        .collect();
+    touches.extend(seam_contacts(&bounds, &ship_circles, &loot_circles));
+
+    let mut collected: Vec<Entity> = Vec::new();
+    for (ship_entity, loot_entity) in touches {
+        if collected.contains(&loot_entity) {
+            continue;
+        }
+        let (Ok((_, player, _, _)), Ok((_, loot, _, _))) =
+            (ships.get(ship_entity), pickups.get(loot_entity))
+        else {
+            continue;
+        };
+
+        commands.entity(loot_entity).despawn();
+        score_events.send(ScoreEvent {
+            player: player.id,
+            points: loot.points,
+        });
+        collected.push(loot_entity);
+    }
+}
+
+/// Clears every pickup when a session is torn down.
+pub fn despawn_loot(mut commands: Commands, loot: Query<Entity, With<Loot>>) {
+    for entity in loot.iter() {
+        commands.entity(entity).despawn();
+    }
+}
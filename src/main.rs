@@ -1,14 +1,99 @@
+use std::path::PathBuf;
+
 use asteroids_rust::GamePlugin;
-use avian2d::prelude::*;
+use asteroids_rust::replay::{Playback, Recording, Replay};
+use asteroids_rust::rng::GameRng;
 use bevy::{prelude::*, sprite::Wireframe2dPlugin};
 
 fn main() {
-    App::new()
-        .add_plugins((
-            DefaultPlugins,
-            Wireframe2dPlugin::default(),
-            GamePlugin,
-            PhysicsPlugins::default(),
-        ))
-        .run();
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{message}");
+            std::process::exit(2);
+        }
+    };
+
+    let mut app = App::new();
+
+    // A seed from the command line takes precedence over a random one
+    if let Some(seed) = options.seed {
+        app.insert_resource(GameRng::new(seed));
+    }
+    if let Some(path) = options.record {
+        app.insert_resource(Recording::to_file(path));
+    }
+    if let Some(path) = options.replay {
+        let replay = match Replay::load(&path) {
+            Ok(replay) => replay,
+            Err(error) => {
+                eprintln!("Failed to load replay {}: {error}", path.display());
+                std::process::exit(1);
+            }
+        };
+        // The recorded seed, mode and world size make the session play out exactly as it did
+        app.insert_resource(GameRng::new(replay.seed))
+            .insert_resource(replay.mode)
+            .insert_resource(replay.bounds)
+            .insert_resource(Playback::new(replay));
+    }
+
+    app.add_plugins((
+        DefaultPlugins,
+        Wireframe2dPlugin,
+        GamePlugin,
+    ))
+    .run();
+}
+
+/// Command line options.
+#[derive(Debug, Default)]
+struct Options {
+    /// `--seed <value>`: seed for the first session
+    seed: Option<u64>,
+    /// `--record <path>`: file every finished session is recorded to
+    record: Option<PathBuf>,
+    /// `--replay <path>`: recording to play back instead of live input
+    replay: Option<PathBuf>,
+}
+
+impl Options {
+    /// Reads the options from the command line arguments.
+    ///
+    /// Every option accepts both `--name <value>` and `--name=<value>`.
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg, None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(format!("{name} needs a value"))
+            };
+
+            match name.as_str() {
+                "--seed" => {
+                    let value = value()?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("Invalid --seed value: {value}"))?;
+                    options.seed = Some(seed);
+                }
+                "--record" => options.record = Some(PathBuf::from(value()?)),
+                "--replay" => options.replay = Some(PathBuf::from(value()?)),
+                _ => return Err(format!("Unknown option: {name}")),
+            }
+        }
+
+        if options.replay.is_some() && (options.seed.is_some() || options.record.is_some()) {
+            return Err("--replay cannot be combined with --seed or --record".to_string());
+        }
+
+        Ok(options)
+    }
 }
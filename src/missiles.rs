@@ -0,0 +1,340 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+
+use crate::asteroids::{self, unique_hits, Asteroid, AsteroidDestroyed, Breakup};
+use crate::bounds::{seam_contacts, BoundingRadius, WorldBounds};
+use crate::particles;
+use crate::rng::GameRng;
+use crate::saucers::{self, Saucer};
+use crate::score::ScoreEvent;
+use crate::weapons::{Faction, Projectile, Weapon};
+
+/// Seconds between two puffs of a missile's engine trail
+const TRAIL_INTERVAL: f32 = 0.04;
+
+/// How a missile finds and chases its target, and how hard it hits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Homing {
+    /// Half-angle in radians of the cone ahead of the missile targets are picked from
+    pub cone: f32,
+    /// Farthest a target can be and still be picked
+    pub range: f32,
+    /// Fastest the missile can turn, in radians per second
+    pub turn_rate: f32,
+    /// Reach of the explosion, which damages every asteroid and saucer it touches
+    pub blast_radius: f32,
+}
+
+/// A homing projectile, riding on a `Bullet` for its lifetime and faction.
+///
+/// The target is picked as the missile leaves the ship and picked again
+/// whenever the one it was chasing is gone.
+#[derive(Component)]
+pub struct Missile {
+    pub homing: Homing,
+    pub target: Option<Entity>,
+    pub trail: Timer,
+}
+
+impl Missile {
+    pub fn new(homing: Homing) -> Self {
+        Self {
+            homing,
+            target: None,
+            trail: Timer::from_seconds(TRAIL_INTERVAL, TimerMode::Repeating),
+        }
+    }
+
+    /// Rotation that turns a missile's mesh, nose along +Y, to face `velocity`.
+    pub fn facing(velocity: Vec2) -> Rotation {
+        Rotation::radians(velocity.to_angle() - std::f32::consts::FRAC_PI_2)
+    }
+}
+
+/// A slow, self-guided missile that blows up everything around what it hits.
+pub struct MissileLauncher;
+
+impl Weapon for MissileLauncher {
+    fn name(&self) -> &'static str {
+        "Missile"
+    }
+
+    fn fire_interval(&self) -> f32 {
+        0.8
+    }
+
+    fn projectile(&self) -> Option<Projectile> {
+        Some(Projectile {
+            speed: 260.0,
+            radius: 3.5,
+            lifetime: 3.0,
+            color: Color::srgb(0.95, 0.95, 0.85),
+            pierce: 0,
+            homing: Some(Homing {
+                cone: 0.6,
+                range: 500.0,
+                turn_rate: 3.0,
+                blast_radius: 60.0,
+            }),
+        })
+    }
+}
+
+/// Picks the nearest of `candidates` inside the cone ahead of `heading`.
+///
+/// Distances and directions are measured around the torus, so a target just
+/// across the wrap seam ahead is as good as one on this side. Ties go to the
+/// lower entity, so the same field always yields the same pick.
+pub fn acquire_target(
+    bounds: &WorldBounds,
+    position: Vec2,
+    heading: Vec2,
+    homing: &Homing,
+    candidates: impl IntoIterator<Item = (Entity, Vec2)>,
+) -> Option<Entity> {
+    candidates
+        .into_iter()
+        .filter_map(|(entity, target)| {
+            let offset = bounds.delta(position, target);
+            let distance = offset.length();
+            let in_cone = distance == 0.0 || heading.angle_between(offset).abs() <= homing.cone;
+            (distance <= homing.range && in_cone).then_some((distance, entity))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)))
+        .map(|(_, entity)| entity)
+}
+
+/// Turns `velocity` towards `desired` by at most `max_turn` radians, keeping its speed.
+pub fn steer(velocity: Vec2, desired: Vec2, max_turn: f32) -> Vec2 {
+    if desired == Vec2::ZERO || velocity == Vec2::ZERO {
+        return velocity;
+    }
+    let turn = velocity.angle_between(desired).clamp(-max_turn, max_turn);
+    Vec2::from_angle(turn).rotate(velocity)
+}
+
+/// Steers every missile towards its target and leaves its engine trail.
+///
+/// Missiles without a live target look for a new one among the asteroids
+/// and saucers ahead of them, and fly straight on while there is none.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn missile_guidance_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    time: Res<Time>,
+    bounds: Res<WorldBounds>,
+    mut rng: ResMut<GameRng>,
+    mut missiles: Query<(
+        &mut Missile,
+        &Position,
+        &BoundingRadius,
+        &mut LinearVelocity,
+        &mut Rotation,
+    )>,
+    asteroids: Query<(Entity, &Position), With<Asteroid>>,
+    saucers: Query<(Entity, &Transform), With<Saucer>>,
+) {
+    let target_position = |entity: Entity| {
+        asteroids
+            .get(entity)
+            .map(|(_, position)| position.0)
+            .or_else(|_| saucers.get(entity).map(|(_, transform)| transform.translation.truncate()))
+            .ok()
+    };
+
+    for (mut missile, position, radius, mut velocity, mut rotation) in missiles.iter_mut() {
+        if missile.target.and_then(target_position).is_none() {
+            let candidates = asteroids
+                .iter()
+                .map(|(entity, position)| (entity, position.0))
+                .chain(
+                    saucers
+                        .iter()
+                        .map(|(entity, transform)| (entity, transform.translation.truncate())),
+                );
+            missile.target =
+                acquire_target(&bounds, position.0, velocity.0, &missile.homing, candidates);
+        }
+
+        if let Some(target) = missile.target.and_then(target_position) {
+            let desired = bounds.delta(position.0, target);
+            let max_turn = missile.homing.turn_rate * time.delta_seconds();
+            velocity.0 = steer(velocity.0, desired, max_turn);
+            *rotation = Missile::facing(velocity.0);
+        }
+
+        missile.trail.tick(time.delta());
+        if missile.trail.just_finished() {
+            let backward = -velocity.0.normalize_or_zero();
+            particles::spawn_engine_particle(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                position.0 + backward * radius.0 * 1.5,
+                backward,
+                &mut rng.cosmetic,
+            );
+        }
+    }
+}
+
+/// Blows up missiles that touch an asteroid or a saucer.
+///
+/// Every asteroid within the blast takes a hit and every saucer within it
+/// is destroyed, with the points going to the player who fired. The blast
+/// reaches across the wrap seam like any other contact. Missiles detonate
+/// before ordinary bullet hits are resolved, so a missile never also counts
+/// as a plain bullet hit.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn missile_detonation_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut collision_events: EventReader<CollisionStarted>,
+    bounds: Res<WorldBounds>,
+    mut rng: ResMut<GameRng>,
+    missiles: Query<(
+        Entity,
+        &Missile,
+        &Faction,
+        &Position,
+        &BoundingRadius,
+        &LinearVelocity,
+    )>,
+    mut asteroids: Query<(
+        Entity,
+        &mut Asteroid,
+        &Position,
+        &BoundingRadius,
+        &LinearVelocity,
+        &AngularVelocity,
+        &ColliderDensity,
+    )>,
+    saucers: Query<(Entity, &Saucer, &Transform)>,
+    mut destroyed_events: EventWriter<AsteroidDestroyed>,
+    mut score_events: EventWriter<ScoreEvent>,
+) {
+    let is_target = |entity: Entity| asteroids.contains(entity) || saucers.contains(entity);
+    let mut hits: Vec<(Entity, Entity)> = collision_events
+        .read()
+        .filter_map(|CollisionStarted(entity1, entity2)| {
+            if missiles.contains(*entity1) && is_target(*entity2) {
+                Some((*entity1, *entity2))
+            } else if missiles.contains(*entity2) && is_target(*entity1) {
+                Some((*entity2, *entity1))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    // Saucers never wrap, so only asteroids can be touched across a seam
+    let missile_circles: Vec<_> = missiles
+        .iter()
+        .map(|(entity, _, _, position, radius, _)| (entity, position.0, radius.0))
+        .collect();
+    let asteroid_circles: Vec<_> = asteroids
+        .iter()
+        .map(|(entity, _, position, radius, ..)| (entity, position.0, radius.0))
+        .collect();
+    hits.extend(seam_contacts(&bounds, &missile_circles, &asteroid_circles));
+
+    let mut detonated: HashSet<Entity> = HashSet::new();
+    let mut destroyed: HashSet<Entity> = HashSet::new();
+
+    for (missile_entity, _) in unique_hits(hits) {
+        if !detonated.insert(missile_entity) {
+            continue;
+        }
+        let Ok((_, missile, faction, center, _, velocity)) = missiles.get(missile_entity) else {
+            continue;
+        };
+        let center = center.0;
+        let blast_radius = missile.homing.blast_radius;
+        commands.entity(missile_entity).despawn();
+        particles::spawn_explosion_particles(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            center,
+            20,
+            Color::srgb(1.0, 0.7, 0.3),
+            &mut rng.cosmetic,
+        );
+
+        let mut caught: Vec<Entity> = asteroids
+            .iter()
+            .filter(|(entity, _, position, radius, ..)| {
+                !destroyed.contains(entity)
+                    && bounds.distance(center, position.0) <= blast_radius + radius.0
+            })
+            .map(|(entity, ..)| entity)
+            .collect();
+        caught.sort_unstable();
+        for asteroid_entity in caught {
+            let Ok((_, mut asteroid, position, _, asteroid_velocity, angular_velocity, density)) =
+                asteroids.get_mut(asteroid_entity)
+            else {
+                continue;
+            };
+            // Pushed straight away from the blast, which adds no spin
+            let impact_offset = bounds.delta(position.0, center);
+            let impact_direction = match -impact_offset {
+                Vec2::ZERO => velocity.0,
+                away => away,
+            };
+            let breakup = Breakup {
+                velocity: asteroid_velocity.0,
+                angular_velocity: angular_velocity.0,
+                mass: density.0 * asteroid.size.mass(),
+                radius: asteroid.size.radius(),
+                impact_direction,
+                impact_offset,
+            };
+            let position = position.0;
+            if asteroids::strike_asteroid(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &mut destroyed_events,
+                asteroid_entity,
+                &mut asteroid,
+                position,
+                *faction,
+                breakup,
+                &mut rng.cosmetic,
+            ) {
+                destroyed.insert(asteroid_entity);
+            }
+        }
+
+        let mut caught: Vec<(Entity, &Saucer, &Transform)> = saucers
+            .iter()
+            .filter(|(entity, saucer, transform)| {
+                !destroyed.contains(entity)
+                    && center.distance(transform.translation.truncate())
+                        <= blast_radius + saucer.size.radius()
+            })
+            .collect();
+        caught.sort_unstable_by_key(|(entity, ..)| *entity);
+        for (saucer_entity, saucer, transform) in caught {
+            saucers::destroy_saucer(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                saucer_entity,
+                transform,
+                &mut rng.cosmetic,
+            );
+            destroyed.insert(saucer_entity);
+            if let Faction::Player(player) = faction {
+                score_events.send(ScoreEvent {
+                    player: *player,
+                    points: saucer.size.points(),
+                });
+            }
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 use rand::prelude::*;
 
+use crate::bounds::ScreenWrap;
 use crate::physics::{InputAccumulator, MovementInputAccumulator, PhysicalRotation};
+use crate::rng::GameRng;
 #[derive(Component)]
 pub struct Particle {
     pub lifetime: Timer,
@@ -38,9 +40,8 @@ pub fn spawn_explosion_particles(
     position: Vec2,
     particle_count: usize,
     base_color: Color,
+    rng: &mut impl Rng,
 ) {
-    let mut rng = thread_rng();
-    
     for _ in 0..particle_count {
         let particle_size = rng.gen_range(1.0..4.0);
         let lifetime = rng.gen_range(0.5..1.5);
@@ -78,6 +79,7 @@ pub fn spawn_explosion_particles(
                 )),
                 ..default()
             },
+            ScreenWrap,
         ));
     }
 }
@@ -120,12 +122,22 @@ pub fn update_particles(
     }
 }
 
+pub fn despawn_particles(
+    mut commands: Commands,
+    particles: Query<Entity, With<Particle>>,
+) {
+    for entity in particles.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
 pub fn spawn_asteroid_destruction_particles(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
     position: Vec2,
     asteroid_size: f32,
+    rng: &mut impl Rng,
 ) {
     let particle_count = ((asteroid_size / 10.0) * 8.0) as usize;
     let base_color = Color::srgb(0.9, 0.6, 0.2); // Orange/yellow explosion color
@@ -137,6 +149,7 @@ pub fn spawn_asteroid_destruction_particles(
         position,
         particle_count,
         base_color,
+        rng,
     );
     
     let sparks_count = ((asteroid_size / 15.0) * 5.0) as usize;
@@ -149,6 +162,7 @@ pub fn spawn_asteroid_destruction_particles(
         position,
         sparks_count,
         spark_color,
+        rng,
     );
 }
 
@@ -159,9 +173,8 @@ pub fn spawn_engine_particle(
     materials: &mut ResMut<Assets<ColorMaterial>>,
     position: Vec2,
     direction: Vec2,
+    rng: &mut impl Rng,
 ) {
-    let mut rng = thread_rng();
-
     let particle_size = rng.gen_range(1.0..3.0);
     let lifetime = rng.gen_range(0.2..0.4);
 
@@ -184,6 +197,7 @@ pub fn spawn_engine_particle(
             transform: Transform::from_translation(position.extend(0.1)),
             ..default()
         },
+        ScreenWrap,
     ));
 }
 
@@ -192,6 +206,7 @@ pub fn engine_particle_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    mut rng: ResMut<GameRng>,
     query: Query<(&MovementInputAccumulator, &Transform, &PhysicalRotation)>,
 ) {
     for (input_acc, transform, rotation) in query.iter() {
@@ -205,6 +220,7 @@ pub fn engine_particle_system(
                 &mut materials,
                 spawn_pos,
                 -forward,
+                &mut rng.cosmetic,
             );
         }
     }
@@ -1,5 +1,10 @@
+use avian2d::prelude::*;
 use bevy::prelude::*;
 
+use crate::controls::{ControlFrame, PlayerControls};
+use crate::input::{Action, Binding, InputBindings};
+use crate::player::{GameMode, Player};
+
 /// Ship thrust force applied when accelerating.
 /// This is the force applied to the ship when thrust input is detected.
 pub const THRUST_FORCE: f32 = 800.0;
@@ -13,10 +18,10 @@ pub const MAX_VELOCITY: f32 = 600.0;
 /// 0.98 means the ship retains 98% of its velocity each second, losing 2% to dampening.
 pub const LINEAR_DAMPENING: f32 = 0.98;
 
-/// Screen boundaries for wrapping
+/// Default playfield size and wrap margin, used for `WorldBounds` until a window reports its size
 pub const SCREEN_WIDTH: f32 = 1280.0;
 pub const SCREEN_HEIGHT: f32 = 720.0;
-pub const WRAP_MARGIN: f32 = 50.0; // How far off-screen before wrapping
+pub const WRAP_MARGIN: f32 = 0.0; // Ghost copies cover the seam, so wrap right at the edge
 
 /// Ship rotation speed in radians per second.
 /// Controls how quickly the ship can turn left or right.
@@ -33,16 +38,16 @@ pub struct PhysicalRotation(pub f32);
 pub struct PreviousPhysicalRotation(pub f32);
 
 /// Represents cardinal movement directions for input handling.
-/// Used to translate keyboard input into directional movement.
+/// Used to translate the bound movement actions into directional movement.
 #[derive(Debug, Component, Clone, Copy, PartialEq)]
 pub enum MoveDirection {
-    /// Upward movement (W key)
+    /// Upward movement (Thrust)
     Up,
-    /// Downward movement (S key)
+    /// Downward movement (Reverse)
     Down,
-    /// Leftward rotation (A key)
+    /// Leftward rotation (RotateLeft)
     Left,
-    /// Rightward rotation (D key)
+    /// Rightward rotation (RotateRight)
     Right,
 }
 
@@ -82,107 +87,119 @@ impl InputAccumulator for MovementInputAccumulator {
     }
 }
 
-/// Represents the ship's current velocity in the physics simulation.
-/// Stored as a 3D vector where z is typically zero for 2D movement.
-#[derive(Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
-pub struct Velocity(pub Vec3);
-
-/// Represents the ship's current position in the physics simulation.
+/// Snapshot of the ship's avian2d `Position` taken after the latest physics step.
 /// This may differ from the rendered position due to interpolation.
 #[derive(Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
 pub struct PhysicalTranslation(pub Vec3);
 
-/// Stores the previous frame's position for interpolation.
+/// Snapshot of the ship's avian2d `Position` taken after the physics step before the latest one.
 /// Used to smoothly render movement between physics updates.
 #[derive(Debug, Component, Clone, Copy, PartialEq, Default, Deref, DerefMut)]
 pub struct PreviousPhysicalTranslation(pub Vec3);
 
 /// Bundle of physics components for the ship.
 /// Provides a convenient way to add all required physics components at once.
-#[derive(Bundle, Default)]
+///
+/// The ship is an avian2d dynamic body like every other object in the game:
+/// avian2d integrates it and resolves its contacts, while the components here
+/// carry input and the snapshots used to interpolate its rendered transform.
+#[derive(Bundle)]
 pub struct ShipPhysicsBundle {
     pub physical_translation: PhysicalTranslation,
     pub previous_physical_translation: PreviousPhysicalTranslation,
     pub physical_rotation: PhysicalRotation,
     pub previous_physical_rotation: PreviousPhysicalRotation,
-    pub velocity: Velocity,
     pub movement_input_accumulator: MovementInputAccumulator,
+    pub rigid_body: RigidBody,
+    pub linear_velocity: LinearVelocity,
+    /// Rotation is driven by input alone, contacts must not spin the ship
+    pub locked_axes: LockedAxes,
 }
 
-/// Processes keyboard input and updates movement accumulators.
+impl Default for ShipPhysicsBundle {
+    fn default() -> Self {
+        Self {
+            physical_translation: PhysicalTranslation::default(),
+            previous_physical_translation: PreviousPhysicalTranslation::default(),
+            physical_rotation: PhysicalRotation::default(),
+            previous_physical_rotation: PreviousPhysicalRotation::default(),
+            movement_input_accumulator: MovementInputAccumulator::default(),
+            rigid_body: RigidBody::Dynamic,
+            linear_velocity: LinearVelocity::ZERO,
+            locked_axes: LockedAxes::ROTATION_LOCKED,
+        }
+    }
+}
+
+/// Feeds this tick's thrust into the movement accumulators.
 /// 
 /// This system:
-/// 1. Detects which direction keys are pressed
-/// 2. Converts key presses to movement vectors
-/// 3. Accumulates movement input for later processing
+/// 1. Reads each ship's thrust axis from its player's `ControlFrame`
+/// 2. Converts it to a movement vector
+/// 3. Accumulates movement input for `apply_movement`
 /// 
-/// Note: Left/Right inputs are handled separately in the rotation system.
+/// Note: Rotation is handled separately in the rotation system.
 pub fn gather_movement_input(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<&mut MovementInputAccumulator>,
+    controls: Res<PlayerControls>,
+    mut query: Query<(&Player, &mut MovementInputAccumulator)>,
 ) {
-    let directions = get_pressed_directions(&keyboard_input);
-
-    for mut input_accumulator in query.iter_mut() {
-        let mut input = Vec2::ZERO;
-
-        for dir in directions.iter() {
-            match dir {
-                MoveDirection::Up => input.y += 1.0,
-                MoveDirection::Down => input.y -= 1.0,
-                MoveDirection::Left => { /* handled in handle_rotation */ }
-                MoveDirection::Right => { /* handled in handle_rotation */ }
-            }
-        }
-        
+    for (player, mut input_accumulator) in query.iter_mut() {
+        let input = Vec2::new(0.0, controls.get(player.id).thrust_axis());
         input_accumulator.accumulate(input);
     }
 }
 
-/// Converts keyboard input into movement directions.
+/// Converts a player's keyboard input into movement directions.
 /// 
-/// Maps WASD keys to their corresponding cardinal directions:
-/// - W → Up
-/// - S → Down
-/// - A → Left
-/// - D → Right
+/// Maps the player's bound movement actions to their corresponding cardinal directions:
+/// - Thrust → Up
+/// - Reverse → Down
+/// - RotateLeft → Left
+/// - RotateRight → Right
 /// 
 /// Returns a vector of all currently pressed directions.
-fn get_pressed_directions(keyboard_input: &ButtonInput<KeyCode>) -> Vec<MoveDirection> {
-    let mut directions = Vec::new();
-
-    if keyboard_input.pressed(KeyCode::KeyW) {
-        directions.push(MoveDirection::Up);
-    }
-    if keyboard_input.pressed(KeyCode::KeyS) {
-        directions.push(MoveDirection::Down);
-    }
-    if keyboard_input.pressed(KeyCode::KeyA) {
-        directions.push(MoveDirection::Left);
-    }
-    if keyboard_input.pressed(KeyCode::KeyD) {
-        directions.push(MoveDirection::Right);
-    }
-
-    directions
+pub fn get_pressed_directions(
+    keyboard_input: &ButtonInput<KeyCode>,
+    bindings: &InputBindings,
+    player: usize,
+) -> Vec<MoveDirection> {
+    [
+        (Action::Thrust, MoveDirection::Up),
+        (Action::Reverse, MoveDirection::Down),
+        (Action::RotateLeft, MoveDirection::Left),
+        (Action::RotateRight, MoveDirection::Right),
+    ]
+    .into_iter()
+    .filter(|(action, _)| bindings.pressed(Binding::new(player, *action), keyboard_input))
+    .map(|(_, direction)| direction)
+    .collect()
 }
 
 /// Applies thrust force based on input with space-like physics.
 /// 
 /// This system:
-/// 1. Gets the accumulated input vector
+/// 1. Gets the input accumulated since the last fixed step
 /// 2. Calculates thrust direction based on ship's rotation
-/// 3. Applies thrust force to current velocity (acceleration)
-/// 4. Clamps velocity to maximum speed
-/// 5. Applies linear dampening for realistic space movement
+/// 3. Applies thrust force to the avian2d linear velocity (acceleration)
+/// 4. Applies linear dampening for realistic space movement
+/// 5. Clamps velocity to maximum speed
+/// 6. Hands the ship's rotation to avian2d so its collider turns with it
+/// 
+/// Runs in `FixedUpdate`, right before avian2d integrates the step.
 pub fn apply_movement(
     fixed_time: Res<Time<Fixed>>,
-    mut query: Query<(&MovementInputAccumulator, &PhysicalRotation, &mut Velocity)>,
+    mut query: Query<(
+        &MovementInputAccumulator,
+        &PhysicalRotation,
+        &mut LinearVelocity,
+        Option<&mut Rotation>,
+    )>,
 ) {
     let dt = fixed_time.delta_seconds();
     
-    for (input_accumulator, rotation, mut velocity) in query.iter_mut() {
-        let input = input_accumulator.get();
+    for (input_accumulator, rotation, mut velocity, body_rotation) in query.iter_mut() {
+        // Several frames may have accumulated input since the last step
+        let input = input_accumulator.get().clamp(Vec2::NEG_ONE, Vec2::ONE);
         
         // Calculate thrust direction based on ship's forward vector
         let forward = Vec2::new(-rotation.0.sin(), rotation.0.cos());
@@ -191,7 +208,7 @@ pub fn apply_movement(
         if input.length() > 0.0 {
             // Thrust is applied in the ship's forward direction
             let thrust = forward * input.y * THRUST_FORCE * dt;
-            velocity.0 += thrust.extend(0.0);
+            velocity.0 += thrust;
         }
         
         // Apply linear dampening to simulate space drag
@@ -199,9 +216,10 @@ pub fn apply_movement(
         velocity.0 *= dampening_factor;
         
         // Clamp velocity to maximum speed
-        let current_speed = velocity.0.length();
-        if current_speed > MAX_VELOCITY {
-            velocity.0 = velocity.0.normalize() * MAX_VELOCITY;
+        velocity.0 = velocity.0.clamp_length_max(MAX_VELOCITY);
+
+        if let Some(mut body_rotation) = body_rotation {
+            *body_rotation = Rotation::radians(rotation.0);
         }
     }
 }
@@ -209,60 +227,55 @@ pub fn apply_movement(
 /// Processes rotation input and updates the ship's orientation.
 /// 
 /// This system:
-/// 1. Detects left/right key presses
-/// 2. Updates the ship's rotation based on input and elapsed time
+/// 1. Reads each ship's rotation axis from its player's `ControlFrame`
+/// 2. Updates the ship's rotation based on input and the fixed timestep
 /// 3. Stores the previous rotation for interpolation
 /// 4. Rotation is immediate and not affected by dampening
 pub fn apply_rotation_input(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    time: Res<Time>,
-    mut query: Query<(&mut PhysicalRotation, &mut PreviousPhysicalRotation)>,
+    controls: Res<PlayerControls>,
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&Player, &mut PhysicalRotation, &mut PreviousPhysicalRotation)>,
 ) {
-    for (mut rotation, mut prev_rotation) in query.iter_mut() {
+    for (player, mut rotation, mut prev_rotation) in query.iter_mut() {
         // Store current rotation for interpolation
         prev_rotation.0 = rotation.0;
 
         // Apply rotation based on input - immediate response, no dampening
-        if keyboard_input.pressed(KeyCode::KeyA) {
-            rotation.0 += ROTATION_SPEED * time.delta_seconds();
-        }
-        if keyboard_input.pressed(KeyCode::KeyD) {
-            rotation.0 -= ROTATION_SPEED * time.delta_seconds();
-        }
+        rotation.0 += ROTATION_SPEED * controls.get(player.id).rotate_axis() * fixed_time.delta_seconds();
     }
 }
 
-/// Updates physics state at a fixed timestep.
+/// Records the result of the latest physics step for interpolation.
 /// 
 /// This system:
-/// 1. Stores the current position for interpolation
-/// 2. Updates position based on velocity and elapsed time
-/// 3. Resets input accumulation for the next frame
+/// 1. Stores the current snapshot as the previous one
+/// 2. Takes a new snapshot of the avian2d position
+/// 3. Resets input accumulation for the next step
 /// 
-/// Running at a fixed timestep ensures consistent physics regardless of framerate.
+/// Runs in `FixedPostUpdate` after avian2d has stepped the simulation, so the
+/// rendered transform always interpolates between two real physics states.
 pub fn update_physics_state(
-    fixed_time: Res<Time<Fixed>>,
     mut query: Query<(
+        &Position,
         &mut PhysicalTranslation,
         &mut PreviousPhysicalTranslation,
         &mut MovementInputAccumulator,
-        &Velocity,
     )>,
 ) {
     for (
+        position,
         mut current_physical_translation,
         mut previous_physical_translation,
         mut input_accumulator,
-        velocity,
     ) in query.iter_mut()
     {
         // Store current position for interpolation
         previous_physical_translation.0 = current_physical_translation.0;
         
-        // Update position based on velocity
-        current_physical_translation.0 += velocity.0 * fixed_time.delta_seconds();
+        // Snapshot the position avian2d integrated
+        current_physical_translation.0 = position.0.extend(current_physical_translation.0.z);
 
-        // Reset input for next frame
+        // Reset input for next step
         input_accumulator.reset();
     }
 }
@@ -313,58 +326,40 @@ pub fn interpolate_rendered_transform(
     }
 }
 
-/// Wraps the ship's position when it goes off-screen.
+/// Resets a ship to its initial state on the tick its player requests a reset.
 /// 
 /// This system:
-/// 1. Checks if the ship has moved beyond screen boundaries
-/// 2. Wraps the ship to the opposite side of the screen
-/// 3. Maintains velocity and rotation during wrapping
-pub fn wrap_screen_position(
-    mut query: Query<(&mut PhysicalTranslation, &mut PreviousPhysicalTranslation)>,
-) {
-    for (mut translation, mut prev_translation) in query.iter_mut() {
-        let half_width = SCREEN_WIDTH / 2.0;
-        let half_height = SCREEN_HEIGHT / 2.0;
-        
-        // Wrap horizontally
-        if translation.0.x > half_width + WRAP_MARGIN {
-            translation.0.x = -half_width - WRAP_MARGIN;
-            prev_translation.0.x = translation.0.x;
-        } else if translation.0.x < -half_width - WRAP_MARGIN {
-            translation.0.x = half_width + WRAP_MARGIN;
-            prev_translation.0.x = translation.0.x;
-        }
-        
-        // Wrap vertically
-        if translation.0.y > half_height + WRAP_MARGIN {
-            translation.0.y = -half_height - WRAP_MARGIN;
-            prev_translation.0.y = translation.0.y;
-        } else if translation.0.y < -half_height - WRAP_MARGIN {
-            translation.0.y = half_height + WRAP_MARGIN;
-            prev_translation.0.y = translation.0.y;
-        }
-    }
-}
-
-/// Resets the ship to its initial state when the R key is pressed.
-/// 
-/// This system:
-/// 1. Detects when the R key is pressed
-/// 2. Resets the ship's position to the origin (0,0,0)
+/// 1. Checks each player's `ControlFrame` for a reset
+/// 2. Resets the ship's position to the player's spawn point
 /// 3. Resets the ship's velocity to zero
 /// 4. Resets the ship's rotation to the default orientation
 pub fn reset_ship_position(
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<(&mut PhysicalTranslation, &mut Velocity, &mut PhysicalRotation)>,
+    controls: Res<PlayerControls>,
+    mode: Res<GameMode>,
+    mut query: Query<(
+        &Player,
+        &mut Position,
+        &mut PhysicalTranslation,
+        &mut PreviousPhysicalTranslation,
+        &mut LinearVelocity,
+        &mut PhysicalRotation,
+    )>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::KeyR) {
-        for (mut translation, mut velocity, mut rotation) in query.iter_mut() {
-            // Reset position to origin
-            translation.0 = Vec3::ZERO;
-            // Reset velocity to zero
-            velocity.0 = Vec3::ZERO;
-            // Reset rotation to default (upward facing)
-            rotation.0 = 0.0;
+    for (player, mut position, mut translation, mut prev_translation, mut velocity, mut rotation) in
+        query.iter_mut()
+    {
+        if !controls.get(player.id).pressed(ControlFrame::RESET) {
+            continue;
         }
+
+        // Reset position to the spawn point, snapshots included so interpolation does not streak
+        let spawn_point = player.spawn_point(*mode);
+        position.0 = spawn_point;
+        translation.0 = spawn_point.extend(0.0);
+        prev_translation.0 = translation.0;
+        // Reset velocity to zero
+        velocity.0 = Vec2::ZERO;
+        // Reset rotation to default (upward facing)
+        rotation.0 = 0.0;
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,16 @@
-use crate::physics;
-// use avian2d::prelude::*;
+use crate::asteroids::Asteroid;
+use crate::bounds::{seam_contacts, BoundingRadius, ScreenWrap, WorldBounds};
+use crate::laser::Energy;
+use crate::particles;
+use crate::physics::{self, PhysicalTranslation, PreviousPhysicalTranslation};
+use crate::rng::GameRng;
+use crate::safety::{MovingCircle, SpawnSafety};
+use crate::score::ScoreEvent;
+use crate::state::GameState;
+use crate::weapons::{Bullet, Faction, Loadout};
+use avian2d::prelude::*;
 use bevy::prelude::*;
+use rand::Rng;
 
 /// Provides a name for an entity
 ///
@@ -15,15 +25,207 @@ impl Name {
     }
 }
 
-/// Spawn the player sprite and a 2D camera.
+/// Most players that can share a session
+pub const MAX_PLAYERS: usize = 2;
+
+/// Marks a ship and says which player flies it.
 ///
-/// It sets up the player's ship and camera in the game world.
-pub fn spawn_player(
-    mut commands: Commands,
-    _asset_server: Res<AssetServer>,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
-) {
+/// Ids run from 0 to `MAX_PLAYERS - 1` and index every per-player table:
+/// controls, bindings, lives and score.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Player {
+    pub id: usize,
+}
+
+impl Player {
+    pub fn new(id: usize) -> Self {
+        Self { id }
+    }
+
+    /// Where this player's ship appears, side by side with the others in two-player games.
+    pub fn spawn_point(self, mode: GameMode) -> Vec2 {
+        match mode {
+            GameMode::Single => Vec2::ZERO,
+            GameMode::Coop | GameMode::Versus => {
+                let offset = SPAWN_SPACING * (self.id as f32 - 0.5);
+                Vec2::new(offset, 0.0)
+            }
+        }
+    }
+
+    /// Fill color of this player's ship.
+    pub fn color(self) -> Color {
+        match self.id {
+            0 => Color::srgb(0.0, 0.0, 1.0),
+            _ => Color::srgb(0.0, 0.8, 0.3),
+        }
+    }
+}
+
+/// How many people are playing and whether they are on the same side.
+///
+/// Chosen on the main menu. In co-op both ships clear the field together and
+/// their shots pass through each other; in versus shots destroy the other ship.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Single,
+    Coop,
+    Versus,
+}
+
+impl GameMode {
+    /// Number of ships in play.
+    pub fn player_count(self) -> usize {
+        match self {
+            GameMode::Single => 1,
+            GameMode::Coop | GameMode::Versus => 2,
+        }
+    }
+
+    /// Every player taking part, in id order.
+    pub fn players(self) -> impl Iterator<Item = Player> {
+        (0..self.player_count()).map(Player::new)
+    }
+
+    /// The mode after this one, in the order the main menu cycles through them.
+    pub fn next(self) -> GameMode {
+        match self {
+            GameMode::Single => GameMode::Coop,
+            GameMode::Coop => GameMode::Versus,
+            GameMode::Versus => GameMode::Single,
+        }
+    }
+
+    /// Name of the mode as shown on the main menu.
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Single => "one player",
+            GameMode::Coop => "co-op",
+            GameMode::Versus => "versus",
+        }
+    }
+
+    /// Identifier used for the mode in replay files.
+    pub fn id(self) -> u8 {
+        match self {
+            GameMode::Single => 0,
+            GameMode::Coop => 1,
+            GameMode::Versus => 2,
+        }
+    }
+
+    /// Looks a mode up by its file identifier.
+    pub fn from_id(id: u8) -> Option<GameMode> {
+        [GameMode::Single, GameMode::Coop, GameMode::Versus]
+            .into_iter()
+            .find(|mode| mode.id() == id)
+    }
+}
+
+/// Spawn the 2D camera used for the whole lifetime of the app.
+pub fn spawn_camera(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
+/// Number of ships each player starts a session with
+pub const STARTING_LIVES: u32 = 3;
+
+/// Horizontal distance between the two ships' spawn points
+pub const SPAWN_SPACING: f32 = 200.0;
+
+/// Points for shooting down the other player's ship in versus
+pub const VERSUS_KILL_POINTS: u32 = 1000;
+
+/// Seconds between the ship being destroyed and the next one appearing
+pub const RESPAWN_DELAY: f32 = 2.0;
+
+/// Seconds a freshly respawned ship ignores asteroid contact
+pub const INVULNERABILITY_DURATION: f32 = 3.0;
+
+/// Seconds between visibility toggles while the ship is invulnerable
+pub const BLINK_INTERVAL: f32 = 0.1;
+
+/// Remaining ships for each player in the current session, including the one in play.
+///
+/// Players not taking part in the session have none.
+#[derive(Resource, Debug)]
+pub struct Lives {
+    pub remaining: [u32; MAX_PLAYERS],
+}
+
+impl Default for Lives {
+    fn default() -> Self {
+        Self::new(GameMode::Single)
+    }
+}
+
+impl Lives {
+    /// Full lives for every player taking part in `mode`.
+    pub fn new(mode: GameMode) -> Self {
+        let mut remaining = [0; MAX_PLAYERS];
+        remaining[..mode.player_count()].fill(STARTING_LIVES);
+        Self { remaining }
+    }
+
+    /// Returns true once every player has lost their last ship.
+    pub fn all_lost(&self) -> bool {
+        self.remaining.iter().all(|lives| *lives == 0)
+    }
+}
+
+/// Counts down to a player's next ship after their current one is destroyed.
+///
+/// One exists for each player without a ship in play. When it finishes,
+/// either a new ship is spawned or, if that player has no lives left, they
+/// are out; once every player is out the session ends.
+#[derive(Component)]
+pub struct RespawnTimer {
+    pub player: Player,
+    pub timer: Timer,
+}
+
+impl RespawnTimer {
+    pub fn new(player: Player) -> Self {
+        Self {
+            player,
+            timer: Timer::from_seconds(RESPAWN_DELAY, TimerMode::Once),
+        }
+    }
+}
+
+/// Makes the ship immune to asteroid contact while it blinks.
+#[derive(Component)]
+pub struct Invulnerable {
+    pub timer: Timer,
+    pub blink: Timer,
+}
+
+impl Default for Invulnerable {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(INVULNERABILITY_DURATION, TimerMode::Once),
+            blink: Timer::from_seconds(BLINK_INTERVAL, TimerMode::Repeating),
+        }
+    }
+}
+
+/// Radius of a circle enclosing the ship: the distance from its centroid to the nose
+pub const SHIP_RADIUS: f32 = 66.666666 * SHIP_SCALE;
+
+/// Scale applied to the ship's mesh and collider
+const SHIP_SCALE: f32 = 0.4;
+
+/// Spawns a player's ship at `spawn_point` and returns its entity.
+///
+/// Shared by the session start and the respawn after losing a life.
+pub fn spawn_ship(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    player: Player,
+    spawn_point: Vec2,
+) -> Entity {
     // Center the mesh on its centroid so rotation pivots around the middle.
     let nose_point = Vec2::new(0.0, 66.666666);
     let bottom_left_point = Vec2::new(-50.0, -33.333332);
@@ -34,33 +236,304 @@ pub fn spawn_player(
         bottom_left_point - center_point,
         bottom_right_point - center_point,
     ));
-    let ship_color = Color::srgb(0.0, 0.0, 1.0);
-
-    // let vertices = vec![
-    //     nose_point - center_point,         // Vertex 0
-    //     bottom_left_point - center_point,  // Vertex 1
-    //     bottom_right_point - center_point, // Vertex 2
-    // ];
-    // let indices = vec![[0, 1, 2]]; // One triangle
-    // let ship_collider = Collider::triangle(
-    //     (nose_point - center_point),
-    //     (bottom_left_point - center_point),
-    //     (bottom_right_point - center_point),
-    // );
-
-    // Spawn camera
-    commands.spawn(Camera2dBundle::default());
+    let ship_color = player.color();
 
-    commands.spawn((
-        Name::new("Player"),
-        ColorMesh2dBundle {
-            mesh: ship_mesh.into(),
-            material: materials.add(ship_color),
-            transform: Transform::from_scale(Vec3::splat(0.4)),
-            ..default()
-        },
-        physics::ShipPhysicsBundle::default(),
-        // RigidBody::Dynamic, // Avian2D component
-        // ship_collider, // Avian2D component
-    ));
+    // The collider is scaled together with the transform, so it matches the mesh.
+    let ship_collider = Collider::triangle(
+        nose_point - center_point,
+        bottom_left_point - center_point,
+        bottom_right_point - center_point,
+    );
+
+    commands
+        .spawn((
+            player,
+            Name(format!("Player {}", player.id + 1)),
+            ColorMesh2dBundle {
+                mesh: ship_mesh.into(),
+                material: materials.add(ship_color),
+                transform: Transform::from_translation(spawn_point.extend(0.0))
+                    .with_scale(Vec3::splat(SHIP_SCALE)),
+                ..default()
+            },
+            physics::ShipPhysicsBundle {
+                physical_translation: PhysicalTranslation(spawn_point.extend(0.0)),
+                previous_physical_translation: PreviousPhysicalTranslation(spawn_point.extend(0.0)),
+                ..default()
+            },
+            Position(spawn_point),
+            ship_collider,
+            BoundingRadius(SHIP_RADIUS),
+            ScreenWrap,
+            Loadout::default(),
+            Energy::default(),
+        ))
+        .id()
+}
+
+/// Spawn the players' ships.
+///
+/// It sets up one ship per player in the game world at the start of a session.
+pub fn spawn_player(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut lives: ResMut<Lives>,
+    mode: Res<GameMode>,
+) {
+    *lives = Lives::new(*mode);
+    for player in mode.players() {
+        let spawn_point = player.spawn_point(*mode);
+        spawn_ship(&mut commands, &mut meshes, &mut materials, player, spawn_point);
+    }
+}
+
+/// Destroys ships that touch an asteroid.
+///
+/// Each ship hit is replaced by an explosion, its player loses a life and
+/// their respawn countdown starts. Invulnerable ships are left alone.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn ship_asteroid_collision_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut collision_events: EventReader<CollisionStarted>,
+    bounds: Res<WorldBounds>,
+    mut rng: ResMut<GameRng>,
+    ships: Query<
+        (Entity, &Player, &Transform, &Position, &BoundingRadius),
+        Without<Invulnerable>,
+    >,
+    asteroids: Query<(Entity, &Position, &BoundingRadius), With<Asteroid>>,
+    mut lives: ResMut<Lives>,
+) {
+    let mut hit_ships: Vec<Entity> = collision_events
+        .read()
+        .filter_map(|CollisionStarted(entity1, entity2)| {
+            if ships.contains(*entity1) && asteroids.contains(*entity2) {
+                Some(*entity1)
+            } else if ships.contains(*entity2) && asteroids.contains(*entity1) {
+                Some(*entity2)
+            } else {
+                None
+            }
+        })
+        .collect();
+    // Asteroids poking in from the opposite edge are invisible to avian2d
+    let ship_circles: Vec<_> = ships
+        .iter()
+        .map(|(entity, _, _, position, radius)| (entity, position.0, radius.0))
+        .collect();
+    let asteroid_circles: Vec<_> = asteroids
+        .iter()
+        .map(|(entity, position, radius)| (entity, position.0, radius.0))
+        .collect();
+    hit_ships.extend(
+        seam_contacts(&bounds, &ship_circles, &asteroid_circles)
+            .into_iter()
+            .map(|(ship, _)| ship),
+    );
+
+    // A ship touching several asteroids at once still only loses one life
+    hit_ships.sort();
+    hit_ships.dedup();
+    for ship_entity in hit_ships {
+        if let Ok((_, player, transform, _, _)) = ships.get(ship_entity) {
+            destroy_ship(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                ship_entity,
+                *player,
+                transform.translation.truncate(),
+                &mut lives,
+                &mut rng.cosmetic,
+            );
+        }
+    }
+}
+
+/// Destroys ships hit by the other player's bullets in versus games.
+///
+/// The shooter is awarded `VERSUS_KILL_POINTS`. A player's own bullets and
+/// invulnerable ships are left alone.
+#[allow(clippy::too_many_arguments)]
+pub fn ship_bullet_collision_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut collision_events: EventReader<CollisionStarted>,
+    ships: Query<(&Player, &Transform), Without<Invulnerable>>,
+    bullets: Query<&Faction, With<Bullet>>,
+    mut lives: ResMut<Lives>,
+    mut score_events: EventWriter<ScoreEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    let mut destroyed: Vec<Entity> = Vec::new();
+
+    for CollisionStarted(entity1, entity2) in collision_events.read() {
+        for (ship_entity, bullet_entity) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            if destroyed.contains(&ship_entity) || destroyed.contains(&bullet_entity) {
+                continue;
+            }
+            let (Ok((player, transform)), Ok(Faction::Player(shooter))) =
+                (ships.get(ship_entity), bullets.get(bullet_entity))
+            else {
+                continue;
+            };
+            if *shooter == player.id {
+                continue;
+            }
+
+            commands.entity(bullet_entity).despawn();
+            destroy_ship(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                ship_entity,
+                *player,
+                transform.translation.truncate(),
+                &mut lives,
+                &mut rng.cosmetic,
+            );
+            score_events.send(ScoreEvent {
+                player: *shooter,
+                points: VERSUS_KILL_POINTS,
+            });
+            destroyed.extend([ship_entity, bullet_entity]);
+        }
+    }
+}
+
+/// Removes a player's ship, leaves an explosion in its place and starts their respawn countdown.
+#[allow(clippy::too_many_arguments)]
+pub fn destroy_ship(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    ship_entity: Entity,
+    player: Player,
+    position: Vec2,
+    lives: &mut Lives,
+    rng: &mut impl Rng,
+) {
+    commands.entity(ship_entity).despawn();
+    let remaining = &mut lives.remaining[player.id];
+    *remaining = remaining.saturating_sub(1);
+    commands.spawn(RespawnTimer::new(player));
+
+    particles::spawn_explosion_particles(
+        commands,
+        meshes,
+        materials,
+        position,
+        40,
+        Color::srgb(0.4, 0.6, 1.0),
+        rng,
+    );
+}
+
+/// Brings a player's next ship in once their respawn countdown finishes.
+///
+/// The new ship starts with a window of invulnerability at the player's spawn
+/// point, or if an asteroid is bearing down on it, at another point that
+/// `SpawnSafety` finds clear. A player with no lives left is out instead, and
+/// once every player is out the session moves to the game over screen.
+#[allow(clippy::too_many_arguments)]
+pub fn respawn_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut respawn_timers: Query<(Entity, &mut RespawnTimer)>,
+    asteroids: Query<(&Position, &LinearVelocity, &BoundingRadius), With<Asteroid>>,
+    lives: Res<Lives>,
+    mode: Res<GameMode>,
+    time: Res<Time>,
+    bounds: Res<WorldBounds>,
+    safety: Res<SpawnSafety>,
+    mut rng: ResMut<GameRng>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let hazards: Vec<MovingCircle> = asteroids
+        .iter()
+        .map(|(position, velocity, radius)| MovingCircle {
+            position: position.0,
+            velocity: velocity.0,
+            radius: radius.0,
+        })
+        .collect();
+
+    for (timer_entity, mut respawn_timer) in respawn_timers.iter_mut() {
+        respawn_timer.timer.tick(time.delta());
+        if !respawn_timer.timer.finished() {
+            continue;
+        }
+
+        commands.entity(timer_entity).despawn();
+        let player = respawn_timer.player;
+
+        if lives.remaining[player.id] == 0 {
+            if lives.all_lost() {
+                next_state.set(GameState::GameOver);
+            }
+            continue;
+        }
+
+        // The usual spawn point first, then random points away from the edges
+        let spawn_point = safety.choose(&bounds, &hazards, |attempt| {
+            let point = if attempt == 0 {
+                player.spawn_point(*mode)
+            } else {
+                let extents = bounds.half_extents * 0.8;
+                Vec2::new(
+                    rng.gameplay.gen_range(-extents.x..extents.x),
+                    rng.gameplay.gen_range(-extents.y..extents.y),
+                )
+            };
+            let circle = MovingCircle {
+                position: point,
+                velocity: Vec2::ZERO,
+                radius: SHIP_RADIUS,
+            };
+            (point, circle)
+        });
+        let ship_entity = spawn_ship(&mut commands, &mut meshes, &mut materials, player, spawn_point);
+        commands.entity(ship_entity).insert(Invulnerable::default());
+    }
+}
+
+/// Blinks invulnerable ships and makes them vulnerable again once the window expires.
+pub fn invulnerability_system(
+    mut commands: Commands,
+    mut ships: Query<(Entity, &mut Invulnerable, &mut Visibility)>,
+    time: Res<Time>,
+) {
+    for (entity, mut invulnerable, mut visibility) in ships.iter_mut() {
+        invulnerable.timer.tick(time.delta());
+        invulnerable.blink.tick(time.delta());
+
+        if invulnerable.timer.finished() {
+            *visibility = Visibility::Inherited;
+            commands.entity(entity).remove::<Invulnerable>();
+            continue;
+        }
+
+        if invulnerable.blink.just_finished() {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+}
+
+/// Despawns the players' ships and pending respawns when a session is torn down.
+#[allow(clippy::type_complexity)]
+pub fn despawn_player(
+    mut commands: Commands,
+    players: Query<Entity, Or<(With<Player>, With<RespawnTimer>)>>,
+) {
+    for entity in players.iter() {
+        commands.entity(entity).despawn();
+    }
 }
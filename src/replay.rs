@@ -0,0 +1,349 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+
+use crate::bounds::WorldBounds;
+use crate::controls::{ControlFrame, PlayerControls};
+use crate::player::GameMode;
+use crate::rng::GameRng;
+use crate::state::GameState;
+
+/// First bytes of every replay file
+const MAGIC: &[u8; 4] = b"ASTR";
+/// Version of the replay format written by this build
+const FORMAT_VERSION: u8 = 2;
+/// Version written before two-player mode, still read as one-player replays
+const SINGLE_PLAYER_VERSION: u8 = 1;
+/// Size of the header: magic, version, game mode, seed, half extents, margin and tick count
+const HEADER_LEN: usize = 4 + 1 + 1 + 8 + 4 * 3 + 4;
+/// Size of a version 1 header, which has no game mode
+const SINGLE_PLAYER_HEADER_LEN: usize = HEADER_LEN - 1;
+/// Size of one player's frame within a run
+const FRAME_LEN: usize = 3;
+
+/// Errors that can occur while reading or writing a replay.
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    /// The file does not start with the replay magic bytes
+    NotAReplay,
+    /// The file was written by a newer version of the game
+    UnsupportedVersion(u8),
+    /// The header names a game mode this build does not know
+    UnknownMode(u8),
+    /// The file ends before all of its ticks were read
+    Truncated,
+    /// A run repeats a frame zero times, or the runs hold more ticks than the header counts
+    Corrupt,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "replay I/O error: {error}"),
+            ReplayError::NotAReplay => write!(f, "file is not a replay"),
+            ReplayError::UnsupportedVersion(version) => {
+                write!(f, "unsupported replay version {version}")
+            }
+            ReplayError::UnknownMode(mode) => write!(f, "unknown game mode {mode}"),
+            ReplayError::Truncated => write!(f, "replay file is truncated"),
+            ReplayError::Corrupt => write!(f, "replay file is corrupt"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+/// A recorded session: everything needed to simulate it again tick for tick.
+///
+/// The seed, game mode and world bounds pin down everything random, size or
+/// player dependent, and `frames` holds the controls for every fixed tick the
+/// session ran.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub bounds: WorldBounds,
+    pub frames: Vec<PlayerControls>,
+}
+
+impl Replay {
+    /// Creates an empty replay for a session started with the given seed, mode and bounds.
+    pub fn new(seed: u64, mode: GameMode, bounds: WorldBounds) -> Self {
+        Self {
+            seed,
+            mode,
+            bounds,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Encodes the replay into its compact binary form.
+    ///
+    /// Frames are run-length encoded, since input rarely changes between ticks,
+    /// and only the players taking part in the session are stored.
+    /// All numbers are little-endian.
+    pub fn encode(&self) -> Vec<u8> {
+        let players = self.mode.player_count();
+        let mut bytes = Vec::with_capacity(HEADER_LEN);
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(FORMAT_VERSION);
+        bytes.push(self.mode.id());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.extend_from_slice(&self.bounds.half_extents.x.to_le_bytes());
+        bytes.extend_from_slice(&self.bounds.half_extents.y.to_le_bytes());
+        bytes.extend_from_slice(&self.bounds.margin.to_le_bytes());
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+
+        let mut frames = self.frames.iter().peekable();
+        while let Some(frame) = frames.next() {
+            let mut run: u16 = 1;
+            while run < u16::MAX && frames.peek() == Some(&frame) {
+                frames.next();
+                run += 1;
+            }
+            bytes.extend_from_slice(&run.to_le_bytes());
+            for frame in &frame.0[..players] {
+                bytes.push(frame.thrust as u8);
+                bytes.push(frame.rotate as u8);
+                bytes.push(frame.buttons);
+            }
+        }
+
+        bytes
+    }
+
+    /// Decodes a replay written by `encode`, or a one-player replay from before two-player mode.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ReplayError> {
+        if bytes.len() < MAGIC.len() || &bytes[..MAGIC.len()] != MAGIC {
+            return Err(ReplayError::NotAReplay);
+        }
+        let Some(&version) = bytes.get(MAGIC.len()) else {
+            return Err(ReplayError::Truncated);
+        };
+
+        // Version 1 has no mode byte, so everything after the version sits one byte earlier
+        let (mode, header_len) = match version {
+            FORMAT_VERSION => {
+                let Some(&mode) = bytes.get(5) else {
+                    return Err(ReplayError::Truncated);
+                };
+                let mode = GameMode::from_id(mode).ok_or(ReplayError::UnknownMode(mode))?;
+                (mode, HEADER_LEN)
+            }
+            SINGLE_PLAYER_VERSION => (GameMode::Single, SINGLE_PLAYER_HEADER_LEN),
+            _ => return Err(ReplayError::UnsupportedVersion(version)),
+        };
+        if bytes.len() < header_len {
+            return Err(ReplayError::Truncated);
+        }
+        let fields = &bytes[header_len - 24..header_len];
+
+        let seed = u64::from_le_bytes(fields[0..8].try_into().unwrap());
+        let f32_at = |offset: usize| f32::from_le_bytes(fields[offset..offset + 4].try_into().unwrap());
+        let bounds = WorldBounds {
+            half_extents: Vec2::new(f32_at(8), f32_at(12)),
+            margin: f32_at(16),
+        };
+        let tick_count = u32::from_le_bytes(fields[20..24].try_into().unwrap()) as usize;
+
+        let players = mode.player_count();
+        let run_len = 2 + FRAME_LEN * players;
+        // The tick count comes straight from the file, so nothing is reserved up front
+        // and a count the runs could never fill is turned away before decoding them
+        let runs = &bytes[header_len..];
+        if tick_count > runs.len() / run_len * u16::MAX as usize {
+            return Err(ReplayError::Truncated);
+        }
+        let mut frames = Vec::new();
+        for run in runs.chunks(run_len) {
+            if run.len() < run_len {
+                return Err(ReplayError::Truncated);
+            }
+            let length = u16::from_le_bytes([run[0], run[1]]) as usize;
+            if length == 0 || frames.len() + length > tick_count {
+                return Err(ReplayError::Corrupt);
+            }
+            let mut controls = PlayerControls::default();
+            for (frame, data) in controls.0.iter_mut().zip(run[2..].chunks(FRAME_LEN)) {
+                *frame = ControlFrame {
+                    thrust: data[0] as i8,
+                    rotate: data[1] as i8,
+                    buttons: data[2],
+                };
+            }
+            frames.extend(std::iter::repeat_n(controls, length));
+        }
+        if frames.len() != tick_count {
+            return Err(ReplayError::Truncated);
+        }
+
+        Ok(Self {
+            seed,
+            mode,
+            bounds,
+            frames,
+        })
+    }
+
+    /// Reads a replay from disk.
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        Self::decode(&fs::read(path)?)
+    }
+
+    /// Writes the replay to disk, replacing any previous file.
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.encode())?;
+        Ok(())
+    }
+}
+
+/// Records the controls of every tick in the current session.
+///
+/// Each new session starts a fresh recording. It is written to `path`, when
+/// there is one, as soon as the session ends, so the file always holds the
+/// latest session.
+#[derive(Resource, Debug, Clone)]
+pub struct Recording {
+    pub path: Option<PathBuf>,
+    pub replay: Replay,
+}
+
+impl Recording {
+    /// Creates a recorder that saves every finished session to `path`.
+    pub fn to_file(path: PathBuf) -> Self {
+        Self {
+            path: Some(path),
+            replay: Replay::new(0, GameMode::default(), WorldBounds::default()),
+        }
+    }
+
+    /// Creates a recorder that only keeps the latest session in memory.
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            replay: Replay::new(0, GameMode::default(), WorldBounds::default()),
+        }
+    }
+}
+
+/// Feeds a recorded session back into the game in place of live input.
+#[derive(Resource, Debug, Clone)]
+pub struct Playback {
+    pub replay: Replay,
+    /// Index of the next frame to play
+    pub cursor: usize,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, cursor: 0 }
+    }
+
+    /// Returns true once every recorded tick has been played.
+    pub fn finished(&self) -> bool {
+        self.cursor >= self.replay.frames.len()
+    }
+}
+
+/// Starts a fresh recording for the session that is about to begin.
+///
+/// Must run before `rng::start_session` so it captures the session's seed.
+pub fn begin_recording(
+    mut recording: ResMut<Recording>,
+    rng: Res<GameRng>,
+    mode: Res<GameMode>,
+    bounds: Res<WorldBounds>,
+) {
+    recording.replay = Replay::new(rng.seed, *mode, *bounds);
+}
+
+/// Appends this tick's controls to the recording.
+pub fn record_controls(mut recording: ResMut<Recording>, controls: Res<PlayerControls>) {
+    recording.replay.frames.push(*controls);
+}
+
+/// Writes the finished session to disk.
+pub fn save_recording(recording: Res<Recording>) {
+    write_recording(&recording);
+}
+
+/// Saves the session in progress when the game is closed mid-play.
+pub fn save_recording_on_exit(
+    mut exits: EventReader<AppExit>,
+    recording: Res<Recording>,
+    state: Res<State<GameState>>,
+) {
+    if exits.read().next().is_none() {
+        return;
+    }
+    if matches!(state.get(), GameState::Playing | GameState::Paused) {
+        write_recording(&recording);
+    }
+}
+
+fn write_recording(recording: &Recording) {
+    let Some(path) = &recording.path else {
+        return;
+    };
+    if recording.replay.frames.is_empty() {
+        return;
+    }
+
+    match recording.replay.save(path) {
+        Ok(()) => info!(
+            "Saved replay of {} ticks to {}",
+            recording.replay.frames.len(),
+            path.display()
+        ),
+        Err(error) => warn!("Failed to save replay to {}: {}", path.display(), error),
+    }
+}
+
+/// Skips the main menu so a replay starts playing right away.
+pub fn start_playback(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Playing);
+}
+
+/// Replaces this tick's controls with the recorded ones.
+///
+/// When the recording runs out the game returns to the main menu, since
+/// anything after that point would be made up.
+pub fn playback_controls(
+    mut playback: ResMut<Playback>,
+    mut controls: ResMut<PlayerControls>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if let Some(frame) = playback.replay.frames.get(playback.cursor) {
+        *controls = *frame;
+        playback.cursor += 1;
+        if playback.finished() {
+            info!("Replay finished after {} ticks", playback.cursor);
+        }
+    } else {
+        *controls = PlayerControls::default();
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+/// Hands control back to the player once a replayed session is over.
+///
+/// A playback that has not started yet is left alone, since the main menu is
+/// also entered on startup.
+pub fn end_playback(mut commands: Commands, playback: Option<Res<Playback>>) {
+    if playback.is_some_and(|playback| playback.cursor > 0) {
+        commands.remove_resource::<Playback>();
+    }
+}
@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use rand::prelude::*;
+
+/// Mixed into the seed so the cosmetic stream never mirrors the gameplay one
+const COSMETIC_STREAM: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Seeded random number generators shared by every spawner.
+///
+/// Randomness is split into two independent streams:
+/// - `gameplay` drives anything that affects the simulation, such as asteroid
+///   placement, fragment velocities, saucers and hyperspace
+/// - `cosmetic` drives purely visual effects like particles
+///
+/// Keeping them apart means toggling or tweaking an effect never changes how a
+/// session plays out, so a logged seed always reproduces the same game.
+#[derive(Resource, Debug, Clone)]
+pub struct GameRng {
+    /// Seed the next session starts from
+    pub seed: u64,
+    pub gameplay: StdRng,
+    pub cosmetic: StdRng,
+}
+
+impl Default for GameRng {
+    /// Picks a fresh seed from the operating system.
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+impl GameRng {
+    /// Creates both streams from the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            gameplay: StdRng::seed_from_u64(seed),
+            cosmetic: StdRng::seed_from_u64(seed ^ COSMETIC_STREAM),
+        }
+    }
+
+    /// Rewinds both streams to the stored seed and returns it.
+    ///
+    /// The stored seed then moves on to a value derived from the old one, so
+    /// every session in a run differs while each stays reproducible from the
+    /// seed returned here.
+    pub fn start_session(&mut self) -> u64 {
+        let session_seed = self.seed;
+        *self = Self::new(session_seed);
+        self.seed = StdRng::seed_from_u64(session_seed).next_u64();
+        session_seed
+    }
+}
+
+/// Logs the seed the first session will use.
+pub fn log_seed(rng: Res<GameRng>) {
+    info!("Game RNG seed: {} (pass --seed {} to replay)", rng.seed, rng.seed);
+}
+
+/// Reseeds the streams at the start of a session and logs the session's seed.
+pub fn start_session(mut rng: ResMut<GameRng>) {
+    let seed = rng.start_session();
+    info!("Starting session with seed {}", seed);
+}
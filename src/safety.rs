@@ -0,0 +1,114 @@
+use bevy::prelude::*;
+
+use crate::bounds::WorldBounds;
+
+/// Keeps new asteroids and respawning ships from appearing on a collision course.
+///
+/// A spawn is safe when, over the next `lookahead` seconds of straight-line
+/// motion, it never comes within `clearance` of anything it is checked against.
+/// Spawners draw candidates until one is safe, and settle for the safest one
+/// if `max_attempts` go by without one.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct SpawnSafety {
+    /// Seconds ahead a spawn's trajectory is checked
+    pub lookahead: f32,
+    /// Gap that has to stay between the two bounding circles the whole time
+    pub clearance: f32,
+    /// Candidates drawn before settling for the safest one
+    pub max_attempts: u32,
+}
+
+impl Default for SpawnSafety {
+    fn default() -> Self {
+        Self {
+            lookahead: 3.0,
+            clearance: 50.0,
+            max_attempts: 16,
+        }
+    }
+}
+
+/// A bounding circle moving in a straight line, as far as the safety check is concerned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovingCircle {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub radius: f32,
+}
+
+/// Smallest distance between the centers of two circles over the next `lookahead` seconds.
+///
+/// Both keep their current velocity. Distances are measured around the torus,
+/// so a circle about to wrap onto the other one is caught too.
+pub fn closest_approach(
+    bounds: &WorldBounds,
+    first: MovingCircle,
+    second: MovingCircle,
+    lookahead: f32,
+) -> f32 {
+    let start = bounds.delta(first.position, second.position);
+    let relative_velocity = second.velocity - first.velocity;
+    let period = bounds.period();
+
+    // The nearest copy of the second circle can change as it moves, so every neighbouring one is checked
+    let mut closest = f32::INFINITY;
+    for x in -1..=1 {
+        for y in -1..=1 {
+            let offset = start + period * Vec2::new(x as f32, y as f32);
+            let speed_squared = relative_velocity.length_squared();
+            let time = if speed_squared > 0.0 {
+                (-offset.dot(relative_velocity) / speed_squared).clamp(0.0, lookahead)
+            } else {
+                0.0
+            };
+            closest = closest.min((offset + relative_velocity * time).length());
+        }
+    }
+    closest
+}
+
+impl SpawnSafety {
+    /// Smallest gap between `candidate` and any of `others` over the lookahead.
+    ///
+    /// Negative once the circles would overlap, and infinite with nothing to check against.
+    pub fn margin(&self, bounds: &WorldBounds, candidate: MovingCircle, others: &[MovingCircle]) -> f32 {
+        others
+            .iter()
+            .map(|other| {
+                closest_approach(bounds, candidate, *other, self.lookahead)
+                    - candidate.radius
+                    - other.radius
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Returns true if `candidate` keeps its clearance from every one of `others`.
+    pub fn is_safe(&self, bounds: &WorldBounds, candidate: MovingCircle, others: &[MovingCircle]) -> bool {
+        self.margin(bounds, candidate, others) >= self.clearance
+    }
+
+    /// Draws candidates until one is safe from `others`.
+    ///
+    /// `draw` is called with the attempt number and returns the spawn along
+    /// with its bounding circle. If no attempt is safe, the spawn that kept
+    /// the widest margin is returned, so a crowded field never stalls a spawn.
+    pub fn choose<T>(
+        &self,
+        bounds: &WorldBounds,
+        others: &[MovingCircle],
+        mut draw: impl FnMut(u32) -> (T, MovingCircle),
+    ) -> T {
+        let mut safest: Option<(T, f32)> = None;
+        for attempt in 0..self.max_attempts.max(1) {
+            let (spawn, circle) = draw(attempt);
+            let margin = self.margin(bounds, circle, others);
+            if margin >= self.clearance {
+                return spawn;
+            }
+            if safest.as_ref().is_none_or(|(_, safest_margin)| margin > *safest_margin) {
+                safest = Some((spawn, margin));
+            }
+        }
+        safest.expect("at least one candidate is drawn").0
+    }
+}
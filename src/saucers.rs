@@ -0,0 +1,411 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use crate::bounds::WorldBounds;
+use crate::particles;
+use crate::player::{self, Invulnerable, Lives, Player};
+use crate::rng::GameRng;
+use crate::score::{Score, ScoreEvent};
+use crate::weapons::{self, Bullet, Faction, Projectile};
+
+/// Height of the sine wave saucers weave along, in pixels
+const WAVE_AMPLITUDE: f32 = 80.0;
+
+/// Angular frequency of the saucer weave, in radians per second
+const WAVE_FREQUENCY: f32 = 1.5;
+
+/// The slower, magenta rounds saucers fire
+const SAUCER_PROJECTILE: Projectile = Projectile {
+    speed: 300.0,
+    color: Color::srgb(1.0, 0.4, 1.0),
+    ..Projectile::STANDARD
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaucerSize {
+    Large,
+    Small,
+}
+
+impl SaucerSize {
+    /// Half the width of the saucer's hull.
+    pub fn radius(self) -> f32 {
+        match self {
+            SaucerSize::Large => 24.0,
+            SaucerSize::Small => 14.0,
+        }
+    }
+
+    /// Horizontal speed in pixels per second.
+    pub fn speed(self) -> f32 {
+        match self {
+            SaucerSize::Large => 110.0,
+            SaucerSize::Small => 160.0,
+        }
+    }
+
+    /// Points awarded for shooting the saucer down.
+    pub fn points(self) -> u32 {
+        match self {
+            SaucerSize::Large => 200,
+            SaucerSize::Small => 1000,
+        }
+    }
+
+    /// Seconds between shots.
+    pub fn fire_interval(self) -> f32 {
+        match self {
+            SaucerSize::Large => 1.5,
+            SaucerSize::Small => 1.0,
+        }
+    }
+}
+
+/// An enemy flying saucer crossing the screen.
+#[derive(Component)]
+pub struct Saucer {
+    pub size: SaucerSize,
+    /// +1.0 when travelling right, -1.0 when travelling left
+    pub direction: f32,
+    /// Seconds since the saucer appeared, drives the weave
+    pub elapsed: f32,
+    pub fire_timer: Timer,
+}
+
+/// Tuning for saucer appearances and marksmanship.
+#[derive(Resource, Debug, Clone)]
+pub struct SaucerConfig {
+    /// Seconds between saucer appearances
+    pub spawn_interval: f32,
+    /// Score at which small saucers become as common as they get
+    /// and aim with `min_aim_error`
+    pub veteran_score: u32,
+    /// Chance of a small saucer at the start of a session
+    pub min_small_chance: f32,
+    /// Chance of a small saucer once the score reaches `veteran_score`
+    pub max_small_chance: f32,
+    /// Largest aiming error of a small saucer, in radians, at score zero
+    pub max_aim_error: f32,
+    /// Smallest aiming error of a small saucer, in radians
+    pub min_aim_error: f32,
+}
+
+impl Default for SaucerConfig {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 15.0,
+            veteran_score: 40_000,
+            min_small_chance: 0.15,
+            max_small_chance: 0.8,
+            max_aim_error: 0.6,
+            min_aim_error: 0.05,
+        }
+    }
+}
+
+impl SaucerConfig {
+    /// How far the player has progressed towards `veteran_score`, from 0.0 to 1.0.
+    fn progress(&self, score: u32) -> f32 {
+        (score as f32 / self.veteran_score.max(1) as f32).min(1.0)
+    }
+
+    /// Probability that the next saucer is a small one.
+    pub fn small_chance(&self, score: u32) -> f32 {
+        self.min_small_chance + (self.max_small_chance - self.min_small_chance) * self.progress(score)
+    }
+
+    /// Maximum angle, in radians, by which a small saucer misses its aim.
+    pub fn aim_error(&self, score: u32) -> f32 {
+        self.max_aim_error + (self.min_aim_error - self.max_aim_error) * self.progress(score)
+    }
+}
+
+/// Counts down to the next saucer appearance.
+#[derive(Resource)]
+pub struct SaucerSpawnTimer(pub Timer);
+
+impl Default for SaucerSpawnTimer {
+    fn default() -> Self {
+        Self(Timer::from_seconds(
+            SaucerConfig::default().spawn_interval,
+            TimerMode::Repeating,
+        ))
+    }
+}
+
+/// Sends a saucer across the screen whenever the spawn timer fires and none is flying.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_saucer_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut spawn_timer: ResMut<SaucerSpawnTimer>,
+    config: Res<SaucerConfig>,
+    score: Res<Score>,
+    bounds: Res<WorldBounds>,
+    saucers: Query<(), With<Saucer>>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+) {
+    spawn_timer.0.tick(time.delta());
+
+    if !spawn_timer.0.just_finished() || !saucers.is_empty() {
+        return;
+    }
+
+    let size = if rng.gameplay.gen_bool(config.small_chance(score.best()).clamp(0.0, 1.0) as f64) {
+        SaucerSize::Small
+    } else {
+        SaucerSize::Large
+    };
+    spawn_saucer(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        &bounds,
+        size,
+        &mut rng.gameplay,
+    );
+}
+
+/// Spawns a saucer just off a random side edge, heading across the screen.
+pub fn spawn_saucer(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    bounds: &WorldBounds,
+    size: SaucerSize,
+    rng: &mut impl Rng,
+) {
+    let radius = size.radius();
+
+    let direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+    let spawn_x = -direction * (bounds.half_extents.x + radius);
+    let lane = (bounds.half_extents.y - WAVE_AMPLITUDE - radius).max(1.0);
+    let spawn_y = rng.gen_range(-lane..lane);
+
+    let saucer_mesh = meshes.add(Ellipse::new(radius, radius * 0.45));
+    let saucer_material = materials.add(Color::srgb(0.9, 0.2, 0.9));
+
+    commands.spawn((
+        Saucer {
+            size,
+            direction,
+            elapsed: 0.0,
+            fire_timer: Timer::from_seconds(size.fire_interval(), TimerMode::Repeating),
+        },
+        ColorMesh2dBundle {
+            mesh: saucer_mesh.into(),
+            material: saucer_material,
+            transform: Transform::from_translation(Vec3::new(spawn_x, spawn_y, 0.0)),
+            ..default()
+        },
+        RigidBody::Kinematic,
+        Sensor,
+        Collider::ellipse(radius, radius * 0.45),
+        LinearVelocity(Vec2::new(direction * size.speed(), 0.0)),
+    ));
+}
+
+/// Steers saucers along their weave and removes them once they leave the screen.
+pub fn saucer_movement_system(
+    mut commands: Commands,
+    mut saucers: Query<(Entity, &mut Saucer, &mut LinearVelocity, &Transform)>,
+    bounds: Res<WorldBounds>,
+    time: Res<Time>,
+) {
+    for (entity, mut saucer, mut velocity, transform) in saucers.iter_mut() {
+        saucer.elapsed += time.delta_seconds();
+
+        // Derivative of the sine path, so position follows amplitude * sin(frequency * t)
+        velocity.0 = Vec2::new(
+            saucer.direction * saucer.size.speed(),
+            WAVE_AMPLITUDE * WAVE_FREQUENCY * (saucer.elapsed * WAVE_FREQUENCY).cos(),
+        );
+
+        let exit_x = bounds.half_extents.x + saucer.size.radius();
+        if transform.translation.x * saucer.direction > exit_x {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Fires saucer bullets.
+///
+/// Large saucers shoot in random directions. Small saucers aim at the ship,
+/// missing by an angle that narrows as the player's score grows. They aim at
+/// the ship's simulated position, not its interpolated rendered one, so
+/// replays see the same shots.
+#[allow(clippy::too_many_arguments)]
+pub fn saucer_fire_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut saucers: Query<(&mut Saucer, &Transform)>,
+    ships: Query<&Position, With<Player>>,
+    config: Res<SaucerConfig>,
+    score: Res<Score>,
+    time: Res<Time>,
+    mut rng: ResMut<GameRng>,
+) {
+    let rng = &mut rng.gameplay;
+
+    for (mut saucer, transform) in saucers.iter_mut() {
+        saucer.fire_timer.tick(time.delta());
+        if !saucer.fire_timer.just_finished() {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        // Small saucers go after whichever ship is closest
+        let target = ships
+            .iter()
+            .map(|ship| ship.0)
+            .min_by(|a, b| a.distance_squared(position).total_cmp(&b.distance_squared(position)));
+        let angle = match (saucer.size, target) {
+            (SaucerSize::Small, Some(target)) => {
+                let to_target = target - position;
+                let error = config.aim_error(score.best());
+                to_target.y.atan2(to_target.x) + rng.gen_range(-error..=error)
+            }
+            _ => rng.gen_range(0.0..std::f32::consts::TAU),
+        };
+        let direction = Vec2::from_angle(angle);
+
+        weapons::spawn_projectile(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &SAUCER_PROJECTILE,
+            position + direction * (saucer.size.radius() + 6.0),
+            direction * SAUCER_PROJECTILE.speed,
+            Faction::Enemy,
+        );
+    }
+}
+
+/// Resolves contacts involving saucers and enemy bullets.
+///
+/// This system:
+/// 1. Destroys saucers hit by player bullets and awards their points to the shooter
+/// 2. Destroys a ship when it is hit by an enemy bullet
+/// 3. Destroys both a ship and the saucer when they ram each other, crediting the ship's player
+///
+/// Enemy bullets touching saucers are ignored, so saucers never shoot each other down.
+#[allow(clippy::too_many_arguments)]
+pub fn saucer_collision_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut collision_events: EventReader<CollisionStarted>,
+    saucers: Query<(&Saucer, &Transform)>,
+    bullets: Query<&Faction, With<Bullet>>,
+    ships: Query<(&Player, &Transform), Without<Invulnerable>>,
+    mut lives: ResMut<Lives>,
+    mut score_events: EventWriter<ScoreEvent>,
+    mut rng: ResMut<GameRng>,
+) {
+    let mut destroyed: Vec<Entity> = Vec::new();
+
+    for CollisionStarted(entity1, entity2) in collision_events.read() {
+        for (first, second) in [(*entity1, *entity2), (*entity2, *entity1)] {
+            if destroyed.contains(&first) || destroyed.contains(&second) {
+                continue;
+            }
+
+            if let (Ok((saucer, transform)), Ok(Faction::Player(shooter))) =
+                (saucers.get(first), bullets.get(second))
+            {
+                commands.entity(second).despawn();
+                destroy_saucer(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    first,
+                    transform,
+                    &mut rng.cosmetic,
+                );
+                score_events.send(ScoreEvent {
+                    player: *shooter,
+                    points: saucer.size.points(),
+                });
+                destroyed.extend([first, second]);
+            } else if let (Ok((player, ship_transform)), Ok(Faction::Enemy)) =
+                (ships.get(first), bullets.get(second))
+            {
+                commands.entity(second).despawn();
+                player::destroy_ship(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    first,
+                    *player,
+                    ship_transform.translation.truncate(),
+                    &mut lives,
+                    &mut rng.cosmetic,
+                );
+                destroyed.extend([first, second]);
+            } else if let (Ok((saucer, transform)), Ok((player, ship_transform))) =
+                (saucers.get(first), ships.get(second))
+            {
+                destroy_saucer(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    first,
+                    transform,
+                    &mut rng.cosmetic,
+                );
+                player::destroy_ship(
+                    &mut commands,
+                    &mut meshes,
+                    &mut materials,
+                    second,
+                    *player,
+                    ship_transform.translation.truncate(),
+                    &mut lives,
+                    &mut rng.cosmetic,
+                );
+                score_events.send(ScoreEvent {
+                    player: player.id,
+                    points: saucer.size.points(),
+                });
+                destroyed.extend([first, second]);
+            }
+        }
+    }
+}
+
+/// Despawns a saucer and leaves an explosion in its place.
+pub fn destroy_saucer(
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    saucer_entity: Entity,
+    transform: &Transform,
+    rng: &mut impl Rng,
+) {
+    commands.entity(saucer_entity).despawn();
+    particles::spawn_explosion_particles(
+        commands,
+        meshes,
+        materials,
+        transform.translation.truncate(),
+        30,
+        Color::srgb(0.9, 0.3, 0.9),
+        rng,
+    );
+}
+
+/// Removes every saucer when a session is torn down.
+pub fn despawn_saucers(mut commands: Commands, saucers: Query<Entity, With<Saucer>>) {
+    for entity in saucers.iter() {
+        commands.entity(entity).despawn();
+    }
+}
+
+/// Restarts the saucer countdown at the start of a session.
+pub fn reset_saucer_timer(mut spawn_timer: ResMut<SaucerSpawnTimer>, config: Res<SaucerConfig>) {
+    spawn_timer.0 = Timer::from_seconds(config.spawn_interval, TimerMode::Repeating);
+}
@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use crate::player::MAX_PLAYERS;
+
+/// Points each player collected during the current session.
+#[derive(Resource, Debug, Default)]
+pub struct Score {
+    /// Indexed by `Player::id`
+    pub points: [u32; MAX_PLAYERS],
+}
+
+impl Score {
+    /// The highest score of any player, which is what the high-score table records.
+    pub fn best(&self) -> u32 {
+        self.points.iter().copied().max().unwrap_or(0)
+    }
+}
+
+/// Awards points to a player.
+///
+/// Any system that rewards a player sends one of these instead of touching
+/// `Score` directly, so scoring rules stay in one place.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ScoreEvent {
+    /// Id of the player who earned the points
+    pub player: usize,
+    pub points: u32,
+}
+
+/// Adds every pending `ScoreEvent` to its player's score.
+pub fn apply_score_events(mut score_events: EventReader<ScoreEvent>, mut score: ResMut<Score>) {
+    for event in score_events.read() {
+        score.points[event.player] += event.points;
+    }
+}
+
+/// Clears the score at the start of a session.
+pub fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
@@ -0,0 +1,123 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+use crate::gamepad::{self, ActiveGamepads};
+use crate::input::{Action, InputBindings};
+use crate::player::GameMode;
+
+/// Top-level flow of the game.
+///
+/// Gameplay systems only run in `Playing`. A session is set up when leaving
+/// `MainMenu` and torn down when coming back to it, so `Paused` and `GameOver`
+/// keep the field intact underneath their overlays.
+#[derive(States, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    /// Title screen shown at startup and after a session ends
+    #[default]
+    MainMenu,
+    /// A session is running and the simulation is live
+    Playing,
+    /// The simulation is frozen until the player resumes
+    Paused,
+    /// The session has ended and the final field is frozen on screen
+    GameOver,
+    /// The key rebinding screen, opened from the main menu
+    Controls,
+}
+
+/// Starts a new session from the main menu.
+///
+/// Enter starts a one-player game, 2 a co-op game and V a versus game. On a
+/// gamepad the mode button cycles through the modes and Start starts the one
+/// picked, which is the last one played until it is changed.
+pub fn start_game(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    active_gamepads: Res<ActiveGamepads>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let chosen = if keyboard_input.just_pressed(KeyCode::Enter) {
+        GameMode::Single
+    } else if gamepad::just_pressed(&active_gamepads, &gamepad_buttons, gamepad::START_BUTTON) {
+        *mode
+    } else if gamepad::just_pressed(&active_gamepads, &gamepad_buttons, gamepad::MODE_BUTTON) {
+        *mode = mode.next();
+        return;
+    } else if keyboard_input.just_pressed(KeyCode::Digit2) {
+        GameMode::Coop
+    } else if keyboard_input.just_pressed(KeyCode::KeyV) {
+        GameMode::Versus
+    } else {
+        return;
+    };
+
+    *mode = chosen;
+    next_state.set(GameState::Playing);
+}
+
+/// Opens the rebinding screen when C is pressed on the main menu.
+pub fn open_controls(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyC) {
+        next_state.set(GameState::Controls);
+    }
+}
+
+/// Toggles between `Playing` and `Paused` when the `Pause` key or the gamepad's Start button is pressed.
+pub fn toggle_pause(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    active_gamepads: Res<ActiveGamepads>,
+    bindings: Res<InputBindings>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !bindings.just_pressed(Action::Pause, &keyboard_input)
+        && !gamepad::just_pressed(&active_gamepads, &gamepad_buttons, gamepad::START_BUTTON)
+    {
+        return;
+    }
+
+    match state.get() {
+        GameState::Playing => next_state.set(GameState::Paused),
+        GameState::Paused => next_state.set(GameState::Playing),
+        _ => {}
+    }
+}
+
+/// Abandons the paused session when Q is pressed.
+pub fn quit_to_menu(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::KeyQ) {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+/// Returns to the main menu from the game over screen when Enter or a gamepad's Start is pressed.
+pub fn leave_game_over(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    active_gamepads: Res<ActiveGamepads>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Enter)
+        || gamepad::just_pressed(&active_gamepads, &gamepad_buttons, gamepad::START_BUTTON)
+    {
+        next_state.set(GameState::MainMenu);
+    }
+}
+
+/// Freezes the avian2d simulation so rigid bodies stop moving.
+pub fn pause_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.pause();
+}
+
+/// Resumes the avian2d simulation.
+pub fn resume_physics(mut physics_time: ResMut<Time<Physics>>) {
+    physics_time.unpause();
+}
@@ -1,10 +1,28 @@
 use bevy::prelude::*;
 
+use crate::highscores::{HighScores, InitialsEntry, INITIALS_LENGTH};
+use crate::input::{bound_key_name, Action, Binding, InputBindings, Rebinding};
+use crate::laser::Energy;
+use crate::player::{GameMode, Lives, Player};
+use crate::score::Score;
+use crate::waves::Wave;
+use crate::weapons::Loadout;
+
+/// Marker for the hint explaining how to toggle wireframes.
+#[derive(Component)]
+pub struct WireframeHintText;
+
+/// Marker for the hint explaining how to fly the ship.
+#[derive(Component)]
+pub struct ControlsHintText;
+
 /// Spawn a bit of UI text to explain how to move the player.
+///
+/// The text itself is filled in by `update_hint_text` from the current bindings.
 pub fn spawn_text(mut commands: Commands) {
-    commands.spawn(TextBundle {
+    commands.spawn((WireframeHintText, TextBundle {
         text: Text::from_section(
-            "Press space to toggle wireframes",
+            "",
             TextStyle {
                 font_size: 30.0,
                 ..default()
@@ -17,11 +35,11 @@ pub fn spawn_text(mut commands: Commands) {
             ..default()
         },
         ..default()
-    });
+    }));
 
-    commands.spawn(TextBundle {
+    commands.spawn((ControlsHintText, TextBundle {
         text: Text::from_section(
-            "Move the player with WASD || Press R to Reset Ship Location",
+            "",
             TextStyle {
                 font_size: 25.0,
                 ..default()
@@ -34,15 +52,421 @@ pub fn spawn_text(mut commands: Commands) {
             ..default()
         },
         ..default()
-    });
+    }));
+}
+
+/// Rewrites the hint texts whenever the key bindings or the game mode change.
+///
+/// Two-player games list each player's keys on a line of their own.
+#[allow(clippy::type_complexity)]
+pub fn update_hint_text(
+    bindings: Res<InputBindings>,
+    mode: Res<GameMode>,
+    mut wireframe_hints: Query<&mut Text, (With<WireframeHintText>, Without<ControlsHintText>)>,
+    mut controls_hints: Query<&mut Text, (With<ControlsHintText>, Without<WireframeHintText>)>,
+) {
+    for mut text in wireframe_hints.iter_mut() {
+        text.sections[0].value = format!(
+            "Press {} to toggle wireframes",
+            bound_key_name(bindings.key(Action::DebugWireframe))
+        );
+    }
+
+    let hints: Vec<String> = mode
+        .players()
+        .map(|player| {
+            let key = |action| bound_key_name(bindings.key(Binding::new(player.id, action)));
+            let who = match mode.player_count() {
+                1 => "the player".to_string(),
+                _ => format!("player {}", player.id + 1),
+            };
+            format!(
+                "Move {who} with {}{}{}{} || Press {} to Reset Ship Location",
+                key(Action::Thrust),
+                key(Action::RotateLeft),
+                key(Action::Reverse),
+                key(Action::RotateRight),
+                key(Action::ResetShip),
+            )
+        })
+        .collect();
+    for mut text in controls_hints.iter_mut() {
+        text.sections[0].value = hints.join("\n");
+    }
+}
+
+/// Marker for the in-game heads-up display, torn down with the session.
+#[derive(Component)]
+pub struct HudUi;
+
+/// Marker for the text showing the remaining lives.
+#[derive(Component)]
+pub struct LivesText;
+
+/// Marker for the text showing the current wave.
+#[derive(Component)]
+pub struct WaveText;
+
+/// Marker for the text showing the weapon each ship has in hand.
+#[derive(Component)]
+pub struct WeaponText;
+
+/// Marker for the "Wave N" banner shown between waves.
+#[derive(Component)]
+pub struct WaveBannerUi;
+
+/// Marker for the text showing the session score.
+#[derive(Component)]
+pub struct ScoreText;
+
+/// Spawns the heads-up display shown while a session is running.
+pub fn spawn_hud(mut commands: Commands) {
+    commands
+        .spawn((
+            HudUi,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(12.0),
+                    right: Val::Px(12.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::FlexEnd,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            let style = TextStyle {
+                font_size: 30.0,
+                ..default()
+            };
+            parent.spawn((ScoreText, TextBundle::from_section("", style.clone())));
+            parent.spawn((WaveText, TextBundle::from_section("", style.clone())));
+            parent.spawn((LivesText, TextBundle::from_section("", style.clone())));
+            parent.spawn((WeaponText, TextBundle::from_section("", style)));
+        });
 }
 
-/// Toggle the wireframe display when the spacebar is pressed.
+/// Keeps the wave number in the HUD in sync with the `Wave` resource.
+pub fn update_wave_text(wave: Res<Wave>, mut query: Query<&mut Text, With<WaveText>>) {
+    if !wave.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = format!("Wave: {}", wave.number);
+    }
+}
+
+/// Spawns the banner announcing the upcoming wave.
+pub fn spawn_wave_banner(mut commands: Commands, wave: Res<Wave>) {
+    let title = format!("WAVE {}", wave.number);
+    spawn_screen(&mut commands, WaveBannerUi, &[&title]);
+}
+
+/// Formats one value per player for the HUD, or just the value in one-player games.
+fn per_player(label: &str, values: &[u32], mode: GameMode) -> String {
+    match mode.player_count() {
+        1 => format!("{label}: {}", values[0]),
+        count => values[..count]
+            .iter()
+            .enumerate()
+            .map(|(id, value)| format!("P{} {label}: {value}", id + 1))
+            .collect::<Vec<_>>()
+            .join("   "),
+    }
+}
+
+/// Keeps the score in the HUD in sync with the `Score` resource.
+pub fn update_score_text(
+    score: Res<Score>,
+    mode: Res<GameMode>,
+    mut query: Query<&mut Text, With<ScoreText>>,
+) {
+    if !score.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = per_player("Score", &score.points, *mode);
+    }
+}
+
+/// Keeps the lives counter in the HUD in sync with the `Lives` resource.
+pub fn update_lives_text(
+    lives: Res<Lives>,
+    mode: Res<GameMode>,
+    mut query: Query<&mut Text, With<LivesText>>,
+) {
+    if !lives.is_changed() {
+        return;
+    }
+
+    for mut text in query.iter_mut() {
+        text.sections[0].value = per_player("Lives", &lives.remaining, *mode);
+    }
+}
+
+/// Keeps the weapon shown in the HUD in sync with each ship's `Loadout`.
+///
+/// Beam weapons also show how much of the ship's energy is left. Players
+/// waiting to respawn are left out until their new ship appears.
+pub fn update_weapon_text(
+    mode: Res<GameMode>,
+    ships: Query<(&Player, &Loadout, &Energy)>,
+    mut query: Query<&mut Text, With<WeaponText>>,
+) {
+    let mut ships: Vec<_> = ships.iter().collect();
+    ships.sort_by_key(|(player, ..)| player.id);
+    let value = ships
+        .iter()
+        .map(|(player, loadout, energy)| {
+            let weapon = loadout.current();
+            let mut name = weapon.name().to_string();
+            if weapon.beam().is_some() {
+                name = format!("{name} {:.0}%", energy.fraction() * 100.0);
+            }
+            match mode.player_count() {
+                1 => format!("Weapon: {name}"),
+                _ => format!("P{} Weapon: {name}", player.id + 1),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("   ");
+
+    for mut text in query.iter_mut() {
+        // Only touch the text when it changes, the loadouts change every tick
+        if text.sections[0].value != value {
+            text.sections[0].value = value.clone();
+        }
+    }
+}
+
+/// Toggle the wireframe display when the `DebugWireframe` key is pressed.
 pub fn toggle_wireframe(
     mut wireframe_config: ResMut<bevy::sprite::Wireframe2dConfig>,
     keyboard: Res<ButtonInput<KeyCode>>,
+    bindings: Res<InputBindings>,
 ) {
-    if keyboard.just_pressed(KeyCode::Space) {
+    if bindings.just_pressed(Action::DebugWireframe, &keyboard) {
         wireframe_config.global = !wireframe_config.global;
     }
 }
+
+/// Marker for entities that make up the main menu screen.
+#[derive(Component)]
+pub struct MainMenuUi;
+
+/// Marker for the main menu line showing the mode a gamepad's Start will pick.
+#[derive(Component)]
+pub struct MenuModeText;
+
+/// Marker for entities that make up the pause overlay.
+#[derive(Component)]
+pub struct PauseUi;
+
+/// Marker for entities that make up the game over screen.
+#[derive(Component)]
+pub struct GameOverUi;
+
+/// Marker for the text showing the initials being typed after a high score.
+#[derive(Component)]
+pub struct InitialsText;
+
+/// Spawns a full-screen column of centered text lines tagged with `marker`.
+///
+/// The first line is drawn as a title and the rest as smaller captions.
+/// Returns the root node so callers can append more content.
+fn spawn_screen(commands: &mut Commands, marker: impl Component, lines: &[&str]) -> Entity {
+    commands
+        .spawn((
+            marker,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    row_gap: Val::Px(16.0),
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            for (index, line) in lines.iter().enumerate() {
+                let font_size = if index == 0 { 64.0 } else { 28.0 };
+                parent.spawn(TextBundle::from_section(
+                    *line,
+                    TextStyle {
+                        font_size,
+                        ..default()
+                    },
+                ));
+            }
+        })
+        .id()
+}
+
+/// Spawns the title screen along with the high-score table.
+pub fn spawn_main_menu(mut commands: Commands, high_scores: Res<HighScores>, mode: Res<GameMode>) {
+    let root = spawn_screen(
+        &mut commands,
+        MainMenuUi,
+        &[
+            "ASTEROIDS",
+            "Press Enter for one player || Press 2 for co-op || Press V for versus",
+            "Press C to configure controls",
+        ],
+    );
+
+    let mut table = String::from("HIGH SCORES\n");
+    if high_scores.entries.is_empty() {
+        table.push_str("No scores yet");
+    }
+    for (rank, entry) in high_scores.entries.iter().enumerate() {
+        table.push_str(&format!(
+            "\n{:>2}. {:<3} {:>7}  wave {:<3} {}",
+            rank + 1,
+            entry.initials,
+            entry.score,
+            entry.wave,
+            entry.date()
+        ));
+    }
+
+    commands.entity(root).with_children(|parent| {
+        parent.spawn((
+            MenuModeText,
+            TextBundle::from_section(
+                menu_mode_line(*mode),
+                TextStyle {
+                    font_size: 28.0,
+                    ..default()
+                },
+            ),
+        ));
+        parent.spawn(
+            TextBundle::from_section(
+                table,
+                TextStyle {
+                    font_size: 22.0,
+                    ..default()
+                },
+            )
+            .with_text_justify(JustifyText::Center),
+        );
+    });
+}
+
+/// Rewrites the main menu's gamepad line when the picked mode changes.
+pub fn update_menu_mode_text(mode: Res<GameMode>, mut query: Query<&mut Text, With<MenuModeText>>) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = menu_mode_line(*mode);
+    }
+}
+
+fn menu_mode_line(mode: GameMode) -> String {
+    format!("Gamepad: Y to change mode ({}) || Start to play", mode.label())
+}
+
+/// Spawns the overlay shown while the game is paused.
+pub fn spawn_pause_overlay(mut commands: Commands, bindings: Res<InputBindings>) {
+    let hint = format!(
+        "Press {} to resume || Press Q to quit to menu",
+        bound_key_name(bindings.key(Action::Pause))
+    );
+    spawn_screen(&mut commands, PauseUi, &["PAUSED", &hint]);
+}
+
+/// Spawns the screen shown once the session has ended.
+///
+/// A qualifying score also gets a line prompting for the player's initials.
+pub fn spawn_game_over_screen(
+    mut commands: Commands,
+    score: Res<Score>,
+    mode: Res<GameMode>,
+    high_scores: Res<HighScores>,
+) {
+    let final_score = format!("Final {}", per_player("score", &score.points, *mode));
+    let root = spawn_screen(
+        &mut commands,
+        GameOverUi,
+        &["GAME OVER", &final_score, "Press Enter or Start to return to the menu"],
+    );
+
+    if high_scores.qualifies(score.best()) {
+        commands.entity(root).with_children(|parent| {
+            parent.spawn((
+                InitialsText,
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font_size: 28.0,
+                        ..default()
+                    },
+                ),
+            ));
+        });
+    }
+}
+
+/// Shows the initials typed so far, or a confirmation once they are saved.
+pub fn update_initials_text(
+    entry: Option<Res<InitialsEntry>>,
+    mut query: Query<&mut Text, With<InitialsText>>,
+) {
+    for mut text in query.iter_mut() {
+        text.sections[0].value = match &entry {
+            Some(entry) => format!(
+                "NEW HIGH SCORE! Enter your initials: {:_<width$}",
+                entry.letters,
+                width = INITIALS_LENGTH
+            ),
+            None => "High score saved".to_string(),
+        };
+    }
+}
+
+/// Despawns every entity tagged with the screen marker `T`, along with its children.
+pub fn despawn_screen<T: Component>(mut commands: Commands, query: Query<Entity, With<T>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Marker for entities that make up the rebinding screen.
+#[derive(Component)]
+pub struct ControlsUi;
+
+/// Redraws the rebinding screen to match the current bindings and selection.
+pub fn update_controls_screen(
+    mut commands: Commands,
+    bindings: Res<InputBindings>,
+    rebinding: Res<Rebinding>,
+    screens: Query<Entity, With<ControlsUi>>,
+) {
+    for entity in screens.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+
+    let mut lines = vec!["CONTROLS".to_string()];
+    for (index, binding) in Binding::all().into_iter().enumerate() {
+        let key = if rebinding.waiting && index == rebinding.selected {
+            "..."
+        } else {
+            bound_key_name(bindings.key(binding))
+        };
+        let cursor = if index == rebinding.selected { ">" } else { " " };
+        lines.push(format!("{cursor} {}: {key}", binding.label()));
+    }
+    lines.push(rebinding.message.clone());
+    lines.push(
+        "Up/Down select || Enter rebind || Delete restore defaults || Escape back".to_string(),
+    );
+
+    let lines: Vec<&str> = lines.iter().map(String::as_str).collect();
+    spawn_screen(&mut commands, ControlsUi, &lines);
+}
@@ -0,0 +1,203 @@
+use bevy::prelude::*;
+
+use crate::asteroids::{self, AsteroidPopulation, AsteroidSize, CompositionWeights};
+use avian2d::prelude::*;
+
+use crate::bounds::{BoundingRadius, WorldBounds};
+use crate::player::Player;
+use crate::rng::GameRng;
+use crate::safety::{MovingCircle, SpawnSafety};
+
+/// Seconds the "Wave N" banner stays up before the wave's asteroids appear
+pub const WAVE_INTERMISSION: f32 = 2.5;
+
+/// Mass a budget may be overshot by, so rounding never leaves out an asteroid that fits
+const BUDGET_SLACK: f32 = 1.0;
+
+/// Parameters for a single wave.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaveConfig {
+    /// Total asteroid mass the field is filled up to when the wave starts
+    pub mass_budget: f32,
+    /// Multiplier applied to the base asteroid velocity
+    pub speed_multiplier: f32,
+    /// Odds of each composition for the wave's asteroids
+    pub composition_weights: CompositionWeights,
+}
+
+/// Table of wave parameters, indexed from wave 1.
+///
+/// Waves beyond the end of the table repeat the last entry, growing by
+/// `extra_mass_per_wave` and `extra_speed_per_wave` for every wave past it
+/// and keeping its composition weights.
+#[derive(Resource, Debug, Clone)]
+pub struct WaveTable {
+    pub waves: Vec<WaveConfig>,
+    pub extra_mass_per_wave: f32,
+    pub extra_speed_per_wave: f32,
+}
+
+impl Default for WaveTable {
+    fn default() -> Self {
+        // Budgets are counted in Large rocks, the size every wave opens with
+        let large = AsteroidSize::Large.mass();
+        Self {
+            waves: vec![
+                WaveConfig {
+                    mass_budget: 4.0 * large,
+                    speed_multiplier: 1.0,
+                    composition_weights: CompositionWeights::default(),
+                },
+                WaveConfig {
+                    mass_budget: 5.0 * large,
+                    speed_multiplier: 1.1,
+                    composition_weights: CompositionWeights { rock: 8, ice: 2, metal: 0, crystal: 0 },
+                },
+                WaveConfig {
+                    mass_budget: 6.0 * large,
+                    speed_multiplier: 1.2,
+                    composition_weights: CompositionWeights { rock: 6, ice: 2, metal: 2, crystal: 0 },
+                },
+                WaveConfig {
+                    mass_budget: 7.0 * large,
+                    speed_multiplier: 1.35,
+                    composition_weights: CompositionWeights { rock: 5, ice: 2, metal: 2, crystal: 1 },
+                },
+                WaveConfig {
+                    mass_budget: 8.0 * large,
+                    speed_multiplier: 1.5,
+                    composition_weights: CompositionWeights { rock: 4, ice: 3, metal: 2, crystal: 1 },
+                },
+            ],
+            extra_mass_per_wave: large,
+            extra_speed_per_wave: 0.1,
+        }
+    }
+}
+
+impl WaveTable {
+    /// Returns the parameters for the given wave number (starting at 1).
+    pub fn config_for(&self, wave: u32) -> WaveConfig {
+        let index = wave.saturating_sub(1) as usize;
+        if let Some(config) = self.waves.get(index) {
+            return *config;
+        }
+
+        let last = self.waves.last().copied().unwrap_or(WaveConfig {
+            mass_budget: 4.0 * AsteroidSize::Large.mass(),
+            speed_multiplier: 1.0,
+            composition_weights: CompositionWeights::default(),
+        });
+        let waves_past_table = index + 1 - self.waves.len();
+
+        WaveConfig {
+            mass_budget: last.mass_budget + self.extra_mass_per_wave * waves_past_table as f32,
+            speed_multiplier: last.speed_multiplier
+                + self.extra_speed_per_wave * waves_past_table as f32,
+            composition_weights: last.composition_weights,
+        }
+    }
+}
+
+/// Sizes of the asteroids that fill `budget` worth of mass, largest first.
+///
+/// Large rocks are used while they fit, and whatever is left over is topped
+/// up with the biggest size that still fits.
+pub fn fill_mass_budget(budget: f32) -> Vec<AsteroidSize> {
+    let mut remaining = budget;
+    let mut sizes = Vec::new();
+    for size in AsteroidSize::ALL {
+        while size.mass() <= remaining + BUDGET_SLACK {
+            sizes.push(size);
+            remaining -= size.mass();
+        }
+    }
+    sizes
+}
+
+/// The wave currently being played, or announced if an intermission is running.
+#[derive(Resource, Debug, Default)]
+pub struct Wave {
+    pub number: u32,
+}
+
+/// Pause between waves while the "Wave N" banner is shown.
+///
+/// Only present between waves; its removal marks the start of the wave.
+#[derive(Resource)]
+pub struct WaveIntermission(pub Timer);
+
+impl Default for WaveIntermission {
+    fn default() -> Self {
+        Self(Timer::from_seconds(WAVE_INTERMISSION, TimerMode::Once))
+    }
+}
+
+/// Resets the wave counter and announces the first wave at the start of a session.
+pub fn start_waves(mut commands: Commands, mut wave: ResMut<Wave>) {
+    wave.number = 1;
+    commands.init_resource::<WaveIntermission>();
+}
+
+/// Drops any pending intermission when a session is torn down.
+pub fn end_waves(mut commands: Commands) {
+    commands.remove_resource::<WaveIntermission>();
+}
+
+/// Drives the wave cycle.
+///
+/// This system:
+/// 1. Counts down the intermission and, when it ends, fills the field up to the wave's mass budget, clear of the ships
+/// 2. Detects when the field has been cleared
+/// 3. Announces the next wave by starting a new intermission
+#[allow(clippy::too_many_arguments)]
+pub fn wave_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut wave: ResMut<Wave>,
+    intermission: Option<ResMut<WaveIntermission>>,
+    wave_table: Res<WaveTable>,
+    population: Res<AsteroidPopulation>,
+    time: Res<Time>,
+    bounds: Res<WorldBounds>,
+    safety: Res<SpawnSafety>,
+    ships: Query<(&Position, &LinearVelocity, &BoundingRadius), With<Player>>,
+    mut rng: ResMut<GameRng>,
+) {
+    if let Some(mut intermission) = intermission {
+        intermission.0.tick(time.delta());
+        if !intermission.0.finished() {
+            return;
+        }
+
+        let config = wave_table.config_for(wave.number);
+        let ships: Vec<MovingCircle> = ships
+            .iter()
+            .map(|(position, velocity, radius)| MovingCircle {
+                position: position.0,
+                velocity: velocity.0,
+                radius: radius.0,
+            })
+            .collect();
+        for size in fill_mass_budget(config.mass_budget - population.mass()) {
+            let composition = config.composition_weights.pick(&mut rng.gameplay);
+            asteroids::spawn_asteroid_at_edge(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &bounds,
+                &safety,
+                &ships,
+                size,
+                composition,
+                config.speed_multiplier,
+                &mut rng.gameplay,
+            );
+        }
+        commands.remove_resource::<WaveIntermission>();
+    } else if population.total() == 0 {
+        wave.number += 1;
+        commands.init_resource::<WaveIntermission>();
+    }
+}
@@ -1,87 +1,428 @@
+use std::time::Duration;
+
 use bevy::prelude::*;
 use avian2d::prelude::*;
-use crate::player::Name;
+use crate::bounds::{BoundingRadius, ScreenWrap};
+use crate::controls::{ControlFrame, PlayerControls};
+use crate::laser::{Beam, LaserBeam};
+use crate::missiles::{Homing, Missile, MissileLauncher};
+use crate::physics::{PhysicalRotation, PhysicalTranslation};
+use crate::player::Player;
 
 #[derive(Resource)]
 pub struct ShootTimer(pub Timer);
 
-#[derive(Resource)]
-pub struct ShootCooldown {
-    pub cooldown_seconds: f32,
-    pub timer: Timer,
+/// Which side fired a projectile.
+///
+/// Player shots destroy asteroids and saucers, and carry the id of the player
+/// who fired them so the points go to the right score. Enemy shots destroy
+/// asteroids and ships but pass through other enemies.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Faction {
+    Player(usize),
+    Enemy,
+}
+
+/// Distance from the ship's center to the muzzle of a standard gun
+pub const MUZZLE_DISTANCE: f32 = 40.0;
+
+/// What a weapon fires: how fast, how big, for how long and through how much.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projectile {
+    /// Speed the projectile leaves the muzzle at
+    pub speed: f32,
+    pub radius: f32,
+    /// Seconds before the projectile fizzles out
+    pub lifetime: f32,
+    pub color: Color,
+    /// Asteroids the projectile passes through before it is spent on the next one
+    pub pierce: u32,
+    /// How the projectile seeks out targets, `None` for rounds that fly straight
+    pub homing: Option<Homing>,
+}
+
+impl Projectile {
+    /// The yellow round every ship starts out firing
+    pub const STANDARD: Projectile = Projectile {
+        speed: 400.0,
+        radius: 3.0,
+        lifetime: 2.0,
+        color: Color::srgb(1.0, 1.0, 0.0),
+        pierce: 0,
+        homing: None,
+    };
+}
+
+/// How the shots of one trigger pull are fanned out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spread {
+    /// Shots fired per trigger pull
+    pub shots: u32,
+    /// Angle in radians between the outermost shots, centered on the nose
+    pub arc: f32,
+}
+
+impl Spread {
+    /// One shot, straight ahead
+    pub const SINGLE: Spread = Spread { shots: 1, arc: 0.0 };
+
+    /// Angle of each shot relative to the nose, from right to left.
+    pub fn angles(&self) -> Vec<f32> {
+        if self.shots <= 1 {
+            return vec![0.0; self.shots as usize];
+        }
+        let step = self.arc / (self.shots - 1) as f32;
+        (0..self.shots)
+            .map(|shot| -self.arc / 2.0 + step * shot as f32)
+            .collect()
+    }
+}
+
+/// A projectile about to leave a ship.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shot {
+    pub position: Vec2,
+    pub velocity: Vec2,
+}
+
+/// A gun a ship can carry.
+///
+/// A weapon only describes itself: the `Loadout` it sits in keeps time
+/// between trigger pulls, and `fire` works out the shots of one pull.
+pub trait Weapon: Send + Sync + 'static {
+    /// Name shown in the HUD
+    fn name(&self) -> &'static str;
+
+    /// Seconds between two trigger pulls, zero for weapons that fire continuously
+    fn fire_interval(&self) -> f32;
+
+    /// What every shot fires, `None` only for beam weapons, which fire no projectiles.
+    ///
+    /// Required so a new gun can't silently fire nothing by leaving it out.
+    fn projectile(&self) -> Option<Projectile>;
+
+    /// Where shots leave the ship, in the ship's frame with the nose along +Y
+    fn muzzle(&self) -> Vec2 {
+        Vec2::new(0.0, MUZZLE_DISTANCE)
+    }
+
+    fn spread(&self) -> Spread {
+        Spread::SINGLE
+    }
+
+    /// The beam a beam weapon projects while fire is held, `None` for guns.
+    ///
+    /// Beams are driven by `laser_system` and fire no projectiles of their own.
+    fn beam(&self) -> Option<Beam> {
+        None
+    }
+
+    /// The shots one trigger pull fires from a ship at `position` turned by `rotation`.
+    fn fire(&self, position: Vec2, rotation: Quat) -> Vec<Shot> {
+        let Some(projectile) = self.projectile() else {
+            return Vec::new();
+        };
+        let forward = (rotation * Vec3::Y).truncate();
+        let muzzle = position + (rotation * self.muzzle().extend(0.0)).truncate();
+        let speed = projectile.speed;
+        self.spread()
+            .angles()
+            .into_iter()
+            .map(|angle| Shot {
+                position: muzzle,
+                velocity: Vec2::from_angle(angle).rotate(forward) * speed,
+            })
+            .collect()
+    }
+}
+
+/// One standard round at a steady rate.
+pub struct SingleShot;
+
+impl Weapon for SingleShot {
+    fn name(&self) -> &'static str {
+        "Single"
+    }
+
+    fn fire_interval(&self) -> f32 {
+        0.2
+    }
+
+    fn projectile(&self) -> Option<Projectile> {
+        Some(Projectile::STANDARD)
+    }
+}
+
+/// A fan of short-lived rounds, for crowds at close range.
+pub struct SpreadShot;
+
+impl Weapon for SpreadShot {
+    fn name(&self) -> &'static str {
+        "Spread"
+    }
+
+    fn fire_interval(&self) -> f32 {
+        0.45
+    }
+
+    fn projectile(&self) -> Option<Projectile> {
+        Some(Projectile {
+            speed: 380.0,
+            lifetime: 0.9,
+            color: Color::srgb(1.0, 0.6, 0.1),
+            ..Projectile::STANDARD
+        })
+    }
+
+    fn spread(&self) -> Spread {
+        Spread { shots: 5, arc: 0.7 }
+    }
+}
+
+/// Small, fast rounds in a steady stream that don't reach far.
+pub struct RapidCannon;
+
+impl Weapon for RapidCannon {
+    fn name(&self) -> &'static str {
+        "Cannon"
+    }
+
+    fn fire_interval(&self) -> f32 {
+        0.07
+    }
+
+    fn projectile(&self) -> Option<Projectile> {
+        Some(Projectile {
+            speed: 520.0,
+            radius: 2.0,
+            lifetime: 0.8,
+            color: Color::srgb(0.5, 1.0, 0.4),
+            ..Projectile::STANDARD
+        })
+    }
 }
 
-impl Default for ShootCooldown {
+/// A slow-firing slug that punches through asteroids in its path.
+pub struct RailShot;
+
+impl Weapon for RailShot {
+    fn name(&self) -> &'static str {
+        "Rail"
+    }
+
+    fn fire_interval(&self) -> f32 {
+        0.9
+    }
+
+    fn projectile(&self) -> Option<Projectile> {
+        Some(Projectile {
+            speed: 900.0,
+            radius: 2.5,
+            lifetime: 1.2,
+            color: Color::srgb(0.3, 0.9, 1.0),
+            pierce: 3,
+            homing: None,
+        })
+    }
+
+    fn muzzle(&self) -> Vec2 {
+        // The slug leaves from the very tip of the nose
+        Vec2::new(0.0, MUZZLE_DISTANCE + 8.0)
+    }
+}
+
+/// The weapons a ship carries and which one is in hand.
+///
+/// The fire cooldown belongs to the ship rather than to a weapon and runs on
+/// across a switch, so switching never skips the wait after a slow weapon.
+#[derive(Component)]
+pub struct Loadout {
+    weapons: Vec<Box<dyn Weapon>>,
+    selected: usize,
+    cooldown: Timer,
+}
+
+impl Default for Loadout {
     fn default() -> Self {
+        Self::new(vec![
+            Box::new(SingleShot),
+            Box::new(SpreadShot),
+            Box::new(RapidCannon),
+            Box::new(RailShot),
+            Box::new(LaserBeam),
+            Box::new(MissileLauncher),
+        ])
+    }
+}
+
+impl Loadout {
+    /// A loadout holding `weapons`, the first one in hand.
+    ///
+    /// Like a fresh ship's, its first shot waits a full fire interval.
+    pub fn new(weapons: Vec<Box<dyn Weapon>>) -> Self {
+        assert!(!weapons.is_empty(), "a loadout needs at least one weapon");
+        let cooldown = Timer::from_seconds(weapons[0].fire_interval(), TimerMode::Once);
         Self {
-            cooldown_seconds: 0.2,
-            timer: Timer::from_seconds(0.2, TimerMode::Once),
+            weapons,
+            selected: 0,
+            cooldown,
         }
     }
+
+    /// The weapon in hand
+    pub fn current(&self) -> &dyn Weapon {
+        self.weapons[self.selected].as_ref()
+    }
+
+    /// Index of the weapon in hand
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn len(&self) -> usize {
+        self.weapons.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.weapons.is_empty()
+    }
+
+    /// Takes up the next weapon, wrapping around after the last.
+    pub fn switch_next(&mut self) {
+        self.selected = (self.selected + 1) % self.weapons.len();
+    }
+
+    /// Counts down the fire cooldown.
+    pub fn tick(&mut self, delta: Duration) {
+        self.cooldown.tick(delta);
+    }
+
+    /// Pulls the trigger, returning the shots fired.
+    ///
+    /// `None` while cooling down, and always for a weapon in hand that fires
+    /// no projectiles, such as a beam.
+    pub fn trigger(&mut self, position: Vec2, rotation: Quat) -> Option<Vec<Shot>> {
+        let weapon = self.current();
+        if !self.cooldown.finished() || weapon.projectile().is_none() {
+            return None;
+        }
+        let shots = weapon.fire(position, rotation);
+        self.cooldown = Timer::from_seconds(weapon.fire_interval(), TimerMode::Once);
+        Some(shots)
+    }
 }
 
+/// A projectile in flight, fired by a ship or a saucer.
 #[derive(Component)]
 pub struct Bullet {
     pub lifetime: Timer,
+    /// Asteroids the bullet can still pass through
+    pub pierce: u32,
+    /// Asteroids the bullet has already passed through, each damaged only once
+    pub struck: Vec<Entity>,
 }
 
 impl Default for Bullet {
     fn default() -> Self {
+        Self::new(&Projectile::STANDARD)
+    }
+}
+
+impl Bullet {
+    pub fn new(projectile: &Projectile) -> Self {
         Self {
-            lifetime: Timer::from_seconds(2.0, TimerMode::Once),
+            lifetime: Timer::from_seconds(projectile.lifetime, TimerMode::Once),
+            pierce: projectile.pierce,
+            struck: Vec::new(),
         }
     }
 }
 
+/// Fires each ship's weapon in hand while its player holds fire, and switches
+/// weapons on request.
+///
+/// Shots leave from the ship's simulated pose rather than its rendered
+/// transform, which is interpolated by frame timing and would make replays drift.
 pub fn shoot_system(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
-    mut shoot_cooldown: ResMut<ShootCooldown>,
     time: Res<Time>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    player_query: Query<&Transform, (With<Name>, Without<Bullet>)>,
+    controls: Res<PlayerControls>,
+    mut ships: Query<(&Player, &PhysicalTranslation, &PhysicalRotation, &mut Loadout)>,
 ) {
-    shoot_cooldown.timer.tick(time.delta());
-    
-    if keyboard_input.pressed(KeyCode::Space) && shoot_cooldown.timer.finished() {
-        if let Ok(player_transform) = player_query.get_single() {
-            spawn_bullet(&mut commands, &mut meshes, &mut materials, player_transform);
-            shoot_cooldown.timer = Timer::from_seconds(shoot_cooldown.cooldown_seconds, TimerMode::Once);
+    for (player, translation, rotation, mut loadout) in ships.iter_mut() {
+        loadout.tick(time.delta());
+        let frame = controls.get(player.id);
+        if frame.pressed(ControlFrame::SWITCH_WEAPON) {
+            loadout.switch_next();
+        }
+        if !frame.pressed(ControlFrame::FIRE) {
+            continue;
+        }
+
+        // Beam weapons fire no projectiles, `laser_system` drives them
+        let Some(projectile) = loadout.current().projectile() else {
+            continue;
+        };
+        let rotation = Quat::from_rotation_z(rotation.0);
+        let Some(shots) = loadout.trigger(translation.0.truncate(), rotation) else {
+            continue;
+        };
+        for shot in shots {
+            spawn_projectile(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                &projectile,
+                shot.position,
+                shot.velocity,
+                Faction::Player(player.id),
+            );
         }
     }
 }
 
-pub fn spawn_bullet(
+/// Spawns a bullet for either faction at `position` travelling at `velocity`.
+///
+/// Homing projectiles become missiles: a `Missile` is added, and they are
+/// drawn as a slim body turned to face their velocity.
+pub fn spawn_projectile(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
-    player_transform: &Transform,
+    projectile: &Projectile,
+    position: Vec2,
+    velocity: Vec2,
+    faction: Faction,
 ) {
-    let bullet_speed = 400.0;
-    let bullet_radius = 3.0;
-    
-    let forward = player_transform.rotation * Vec3::Y;
-    let spawn_offset = forward * 40.0;
-    let spawn_position = player_transform.translation + spawn_offset;
-    
-    let velocity = Vec2::new(forward.x, forward.y) * bullet_speed;
-    
-    let bullet_mesh = meshes.add(Circle::new(bullet_radius));
-    let bullet_material = materials.add(Color::srgb(1.0, 1.0, 0.0));
-    
-    commands.spawn((
-        Bullet::default(),
+    let bullet_mesh = match projectile.homing {
+        Some(_) => meshes.add(Rectangle::new(projectile.radius, projectile.radius * 3.0)),
+        None => meshes.add(Circle::new(projectile.radius)),
+    };
+    let bullet_material = materials.add(projectile.color);
+
+    let mut bullet = commands.spawn((
+        Bullet::new(projectile),
+        faction,
         ColorMesh2dBundle {
             mesh: bullet_mesh.into(),
             material: bullet_material,
-            transform: Transform::from_translation(spawn_position),
+            transform: Transform::from_translation(position.extend(0.0)),
             ..default()
         },
         RigidBody::Kinematic,
-        Collider::circle(bullet_radius),
+        Position(position),
+        // Bullets only need to detect hits, they should never shove what they touch
+        Sensor,
+        Collider::circle(projectile.radius),
+        BoundingRadius(projectile.radius),
         LinearVelocity(velocity),
+        ScreenWrap,
     ));
+    if let Some(homing) = projectile.homing {
+        bullet.insert((Missile::new(homing), Missile::facing(velocity)));
+    }
 }
 
 pub fn bullet_lifetime_system(
@@ -96,3 +437,12 @@ pub fn bullet_lifetime_system(
         }
     }
 }
+
+pub fn despawn_bullets(
+    mut commands: Commands,
+    bullets: Query<Entity, With<Bullet>>,
+) {
+    for entity in bullets.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,429 @@
+use asteroids_rust::asteroids::{
+    Asteroid, AsteroidPopulation, AsteroidSize, Breakup, Composition, CompositionWeights,
+    MAX_IMPACT_SPIN, MAX_OUTLINE_VERTICES, MIN_OUTLINE_VERTICES, OUTLINE_JAGGEDNESS,
+    SEPARATION_SPEED, jagged_outline, outline_mesh, unique_hits,
+};
+use asteroids_rust::bounds::BoundingRadius;
+use asteroids_rust::headless::HeadlessGame;
+use asteroids_rust::loot::{LOOT_POINTS, Loot};
+use asteroids_rust::player::Player;
+use asteroids_rust::rng::GameRng;
+use asteroids_rust::score::Score;
+use asteroids_rust::state::GameState;
+use asteroids_rust::waves::{WaveTable, fill_mass_budget};
+use asteroids_rust::weapons::{Bullet, Faction};
+use avian2d::prelude::{CollisionStarted, LinearVelocity, Position};
+use bevy::prelude::*;
+use bevy::render::mesh::Indices;
+
+/// The main test function that runs all other tests in order
+#[test]
+fn run_all_tests_in_order() {
+    test_outline_keeps_mean_radius();
+    test_outline_never_crosses_itself();
+    test_outline_follows_seed();
+    test_outline_mesh_fans_from_center();
+    test_compositions_split_differently();
+    test_composition_weights();
+    test_later_waves_mix_compositions();
+    test_metal_takes_several_hits_and_drops_loot();
+    test_breakup_conserves_mass_and_momentum();
+    test_fragments_separate_across_the_shot();
+    test_off_center_hits_spin_fragments();
+    test_unique_hits();
+    test_one_bullet_breaks_one_asteroid();
+    test_two_bullets_break_an_asteroid_once();
+    test_population_follows_every_despawn();
+    test_mass_budget_fills_with_the_largest_sizes();
+}
+
+/// Tests that outlines are jagged but average out to the size's radius
+fn test_outline_keeps_mean_radius() {
+    let mut rng = GameRng::new(1);
+    for size in [AsteroidSize::Large, AsteroidSize::Medium, AsteroidSize::Small] {
+        let outline = jagged_outline(size.radius(), &mut rng.gameplay);
+        assert!((MIN_OUTLINE_VERTICES..=MAX_OUTLINE_VERTICES).contains(&outline.len()));
+
+        let distances: Vec<f32> = outline.iter().map(|corner| corner.length()).collect();
+        let mean = distances.iter().sum::<f32>() / distances.len() as f32;
+        assert!((mean - size.radius()).abs() < 1e-3);
+
+        // Corners stray from the mean, but never far enough to pinch the rock to a point
+        assert!(distances.iter().any(|distance| (distance - mean).abs() > 0.01));
+        let limit = (1.0 + OUTLINE_JAGGEDNESS) / (1.0 - OUTLINE_JAGGEDNESS) * size.radius();
+        assert!(distances.iter().all(|distance| *distance > 0.0 && *distance < limit));
+    }
+}
+
+/// Tests that corners run counter-clockwise around the center, so the outline is simple
+fn test_outline_never_crosses_itself() {
+    let mut rng = GameRng::new(2);
+    for _ in 0..50 {
+        let outline = jagged_outline(40.0, &mut rng.gameplay);
+        let angles: Vec<f32> = outline
+            .iter()
+            .map(|corner| corner.y.atan2(corner.x).rem_euclid(std::f32::consts::TAU))
+            .collect();
+        // Only the first corner may sit just below zero and wrap around to the end
+        let start = usize::from(angles[0] > std::f32::consts::PI);
+        assert!(angles[start..].windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
+/// Tests that the same seed always produces the same outline
+fn test_outline_follows_seed() {
+    let first = jagged_outline(25.0, &mut GameRng::new(3).gameplay);
+    let second = jagged_outline(25.0, &mut GameRng::new(3).gameplay);
+    let other = jagged_outline(25.0, &mut GameRng::new(4).gameplay);
+    assert_eq!(first, second);
+    assert_ne!(first, other);
+}
+
+/// Tests that the mesh has the center plus every corner, with one triangle per edge
+fn test_outline_mesh_fans_from_center() {
+    let outline = jagged_outline(15.0, &mut GameRng::new(5).gameplay);
+    let mesh = outline_mesh(&outline);
+
+    assert_eq!(mesh.count_vertices(), outline.len() + 1);
+    let Some(Indices::U32(indices)) = mesh.indices() else {
+        panic!("asteroid meshes use 32-bit indices");
+    };
+    assert_eq!(indices.len(), outline.len() * 3);
+    assert!(indices.chunks(3).all(|triangle| triangle[0] == 0));
+}
+
+/// Tests the split rules: ice shatters to shards, crystal splits in three and smalls crumble
+fn test_compositions_split_differently() {
+    let rock = Composition::Rock;
+    assert_eq!(rock.fragment_size(AsteroidSize::Large), Some(AsteroidSize::Medium));
+    assert_eq!(rock.fragment_count(), 2);
+
+    let ice = Composition::Ice;
+    assert_eq!(ice.fragment_size(AsteroidSize::Large), Some(AsteroidSize::Small));
+    assert!(ice.fragment_count() > rock.fragment_count());
+
+    assert_eq!(Composition::Crystal.fragment_count(), 3);
+    for composition in Composition::ALL {
+        assert_eq!(composition.fragment_size(AsteroidSize::Small), None);
+        assert_eq!(composition.drops_loot(), composition == Composition::Metal);
+    }
+
+    let metal = Asteroid::new(AsteroidSize::Large, Composition::Metal);
+    assert!(metal.hit_points > 1);
+    assert!(metal.points() > AsteroidSize::Large.points());
+}
+
+/// Tests that compositions are drawn by weight and zero weights never come up
+fn test_composition_weights() {
+    let mut rng = GameRng::new(6);
+    let only_metal = CompositionWeights { rock: 0, ice: 0, metal: 1, crystal: 0 };
+    assert!((0..100).all(|_| only_metal.pick(&mut rng.gameplay) == Composition::Metal));
+
+    let none = CompositionWeights { rock: 0, ice: 0, metal: 0, crystal: 0 };
+    assert_eq!(none.pick(&mut rng.gameplay), Composition::Rock);
+
+    let mixed = CompositionWeights { rock: 1, ice: 1, metal: 0, crystal: 1 };
+    let picks: Vec<Composition> = (0..300).map(|_| mixed.pick(&mut rng.gameplay)).collect();
+    assert!(!picks.contains(&Composition::Metal));
+    for composition in [Composition::Rock, Composition::Ice, Composition::Crystal] {
+        let count = picks.iter().filter(|pick| **pick == composition).count();
+        assert!((50..150).contains(&count));
+    }
+}
+
+/// Tests that the first wave is plain rock and waves past the table keep the last mix
+fn test_later_waves_mix_compositions() {
+    let table = WaveTable::default();
+    assert_eq!(table.config_for(1).composition_weights, CompositionWeights::default());
+
+    let last = table.waves.last().unwrap().composition_weights;
+    assert!(last.metal > 0 && last.ice > 0 && last.crystal > 0);
+    assert_eq!(table.config_for(table.waves.len() as u32 + 3).composition_weights, last);
+}
+
+/// Tests that a metal asteroid survives its first hits, then splits and leaves loot to collect
+fn test_metal_takes_several_hits_and_drops_loot() {
+    let mut game = HeadlessGame::new(7);
+    game.start_game();
+    game.step_until_first_wave();
+
+    // One metal rock among plain ones, so its fragments are the only metal in the field
+    let rocks = game.recompose_asteroids(Composition::Rock);
+    let asteroid_entity = *rocks.first().expect("the first wave should have spawned");
+    let asteroid = Asteroid::new(AsteroidSize::Large, Composition::Metal);
+    *game.world_mut().get_mut::<Asteroid>(asteroid_entity).unwrap() = asteroid;
+    let hit_points = asteroid.hit_points;
+
+    for hit in 1..=hit_points {
+        let world = game.world_mut();
+        let position = *world.get::<Position>(asteroid_entity).unwrap();
+        let bullet = world
+            .spawn((
+                Bullet::default(),
+                Faction::Player(0),
+                position,
+                BoundingRadius(3.0),
+                LinearVelocity(Vec2::new(0.0, 400.0)),
+            ))
+            .id();
+        world.send_event(CollisionStarted(bullet, asteroid_entity));
+        game.step(1);
+
+        let remaining = game
+            .world()
+            .get::<Asteroid>(asteroid_entity)
+            .map(|asteroid| asteroid.hit_points);
+        if hit < hit_points {
+            assert_eq!(remaining, Some(hit_points - hit));
+            assert_eq!(game.world().resource::<Score>().points[0], 0);
+        } else {
+            assert_eq!(remaining, None);
+        }
+    }
+
+    let expected = Asteroid::new(AsteroidSize::Large, Composition::Metal).points();
+    assert_eq!(game.world().resource::<Score>().points[0], expected);
+    let world = game.world_mut();
+    let fragments = world
+        .query::<&Asteroid>()
+        .iter(world)
+        .filter(|asteroid| asteroid.composition == Composition::Metal)
+        .count();
+    assert_eq!(fragments, Composition::Metal.fragment_count());
+
+    let loot = world.query_filtered::<Entity, With<Loot>>().single(world);
+    let ship = world.query_filtered::<Entity, With<Player>>().single(world);
+    world.send_event(CollisionStarted(ship, loot));
+    game.step(1);
+    assert_eq!(game.world().resource::<Score>().points[0], expected + LOOT_POINTS);
+    assert!(game.world().get_entity(loot).is_none());
+}
+
+/// A large asteroid drifting right and turning slowly, shot from below
+fn drifting_breakup(impact_offset: Vec2) -> Breakup {
+    Breakup {
+        velocity: Vec2::new(50.0, -20.0),
+        angular_velocity: 0.5,
+        mass: AsteroidSize::Large.mass(),
+        radius: AsteroidSize::Large.radius(),
+        impact_direction: Vec2::new(0.0, 400.0),
+        impact_offset,
+    }
+}
+
+/// Tests that the fragments together weigh and carry exactly what the parent did
+fn test_breakup_conserves_mass_and_momentum() {
+    let breakup = drifting_breakup(Vec2::new(12.0, -30.0));
+    for count in 2..=4 {
+        let fragments = breakup.fragments(count, AsteroidSize::Medium.radius());
+        assert_eq!(fragments.len(), count);
+
+        let mass: f32 = fragments.iter().map(|fragment| fragment.mass).sum();
+        assert!((mass - breakup.mass).abs() / breakup.mass < 1e-5);
+
+        let momentum: Vec2 = fragments
+            .iter()
+            .map(|fragment| fragment.velocity * fragment.mass)
+            .sum();
+        let expected = breakup.velocity * breakup.mass;
+        assert!((momentum - expected).length() / expected.length() < 1e-4);
+    }
+    assert!(breakup.fragments(0, 10.0).is_empty());
+}
+
+/// Tests that two fragments fly apart sideways to the bullet without overlapping
+fn test_fragments_separate_across_the_shot() {
+    let breakup = Breakup {
+        angular_velocity: 0.0,
+        ..drifting_breakup(Vec2::new(0.0, -40.0))
+    };
+    let radius = AsteroidSize::Medium.radius();
+    let fragments = breakup.fragments(2, radius);
+
+    for fragment in &fragments {
+        let separation = fragment.velocity - breakup.velocity;
+        assert!(separation.dot(breakup.impact_direction.normalize()).abs() < 1e-3);
+        assert!((separation.length() - SEPARATION_SPEED).abs() < 1e-3);
+        // Each moves away from the parent's center
+        assert!(separation.dot(fragment.offset) > 0.0);
+    }
+
+    for count in 2..=4 {
+        let fragments = breakup.fragments(count, radius);
+        for (index, first) in fragments.iter().enumerate() {
+            for second in &fragments[index + 1..] {
+                assert!(first.offset.distance(second.offset) >= 2.0 * radius - 1e-3);
+            }
+        }
+    }
+}
+
+/// Tests that a dead-center hit keeps the parent's spin while glancing hits add to it
+fn test_off_center_hits_spin_fragments() {
+    let centered = drifting_breakup(Vec2::new(0.0, -40.0)).fragments(3, 15.0);
+    assert!(centered.iter().all(|fragment| (fragment.angular_velocity - 0.5).abs() < 1e-5));
+
+    // Pushing up on the right side turns the rock counter-clockwise, and on the left clockwise
+    let right = drifting_breakup(Vec2::new(40.0, 0.0)).fragments(2, 25.0);
+    assert!((right[0].angular_velocity - (0.5 + MAX_IMPACT_SPIN)).abs() < 1e-4);
+    let left = drifting_breakup(Vec2::new(-20.0, 0.0)).fragments(2, 25.0);
+    assert!((left[0].angular_velocity - (0.5 - MAX_IMPACT_SPIN / 2.0)).abs() < 1e-4);
+}
+
+/// Tests that repeated contacts are dropped and the rest come out in a fixed order
+fn test_unique_hits() {
+    let bullet = Entity::from_raw(1);
+    let first = Entity::from_raw(2);
+    let second = Entity::from_raw(3);
+    let hits = unique_hits(vec![(bullet, second), (bullet, first), (bullet, second)]);
+    assert_eq!(hits, vec![(bullet, first), (bullet, second)]);
+}
+
+/// Starts a game, waits for the first wave and turns every asteroid into a large rock
+///
+/// The rocks are lined up well apart and clear of the seams, so a bullet on
+/// one of them touches no other.
+fn game_with_rocks(seed: u64) -> (HeadlessGame, Vec<Entity>) {
+    let mut game = HeadlessGame::new(seed);
+    game.start_game();
+    game.step_until_first_wave();
+
+    let rocks = game.recompose_asteroids(Composition::Rock);
+    let world = game.world_mut();
+    for (index, rock) in rocks.iter().enumerate() {
+        let position = Vec2::new(-540.0 + index as f32 * 150.0, 200.0);
+        world.get_mut::<Position>(*rock).unwrap().0 = position;
+        world.get_mut::<Transform>(*rock).unwrap().translation = position.extend(0.0);
+    }
+    assert!(rocks.len() >= 2, "the first wave should have spawned");
+    (game, rocks)
+}
+
+/// Spawns a player bullet sitting on `target`
+fn bullet_on(world: &mut World, target: Entity) -> Entity {
+    let position = *world.get::<Position>(target).unwrap();
+    world
+        .spawn((
+            Bullet::default(),
+            Faction::Player(0),
+            position,
+            BoundingRadius(3.0),
+            LinearVelocity(Vec2::new(0.0, 400.0)),
+        ))
+        .id()
+}
+
+/// Asserts that the asteroid population matches the asteroids actually in the world
+fn assert_count_matches(game: &mut HeadlessGame) {
+    let world = game.world_mut();
+    let asteroids: Vec<AsteroidSize> = world
+        .query::<&Asteroid>()
+        .iter(world)
+        .map(|asteroid| asteroid.size)
+        .collect();
+    let population = world.resource::<AsteroidPopulation>();
+    assert_eq!(population.total(), asteroids.len());
+    for size in AsteroidSize::ALL {
+        let count = asteroids.iter().filter(|asteroid| **asteroid == size).count();
+        assert_eq!(population.count(size), count);
+    }
+}
+
+/// Tests that a bullet touching two asteroids in the same tick only breaks one of them
+fn test_one_bullet_breaks_one_asteroid() {
+    let (mut game, rocks) = game_with_rocks(8);
+    let world = game.world_mut();
+    let bullet = bullet_on(world, rocks[0]);
+    // Reported twice, as avian2d and the seam check may both do
+    world.send_event(CollisionStarted(bullet, rocks[0]));
+    world.send_event(CollisionStarted(rocks[1], bullet));
+    world.send_event(CollisionStarted(bullet, rocks[0]));
+    game.step(1);
+
+    let broken = rocks
+        .iter()
+        .filter(|rock| game.world().get_entity(**rock).is_none())
+        .count();
+    assert_eq!(broken, 1);
+    assert!(game.world().get_entity(bullet).is_none());
+    let points = Asteroid::new(AsteroidSize::Large, Composition::Rock).points();
+    assert_eq!(game.world().resource::<Score>().points[0], points);
+    assert_count_matches(&mut game);
+}
+
+/// Tests that two bullets hitting one rock in the same tick break it once and the spare flies on
+fn test_two_bullets_break_an_asteroid_once() {
+    let (mut game, rocks) = game_with_rocks(9);
+    let world = game.world_mut();
+    let before = world.query::<&Asteroid>().iter(world).count();
+    let first = bullet_on(world, rocks[0]);
+    let second = bullet_on(world, rocks[0]);
+    world.send_event(CollisionStarted(first, rocks[0]));
+    world.send_event(CollisionStarted(second, rocks[0]));
+    game.step(1);
+
+    assert!(game.world().get_entity(rocks[0]).is_none());
+    let spent = [first, second]
+        .iter()
+        .filter(|bullet| game.world().get_entity(**bullet).is_none())
+        .count();
+    assert_eq!(spent, 1);
+
+    let world = game.world_mut();
+    let after = world.query::<&Asteroid>().iter(world).count();
+    assert_eq!(after, before - 1 + Composition::Rock.fragment_count());
+    let points = Asteroid::new(AsteroidSize::Large, Composition::Rock).points();
+    assert_eq!(game.world().resource::<Score>().points[0], points);
+    assert_count_matches(&mut game);
+}
+
+/// Tests that the population keeps up with splits, despawns from outside the game systems and session teardown
+fn test_population_follows_every_despawn() {
+    let (mut game, rocks) = game_with_rocks(10);
+    let large = game.world().resource::<AsteroidPopulation>().count(AsteroidSize::Large);
+    assert_eq!(large, rocks.len());
+
+    let world = game.world_mut();
+    let bullet = bullet_on(world, rocks[0]);
+    world.send_event(CollisionStarted(bullet, rocks[0]));
+    game.step(1);
+    let population = *game.world().resource::<AsteroidPopulation>();
+    assert_eq!(population.count(AsteroidSize::Large), rocks.len() - 1);
+    assert_eq!(population.count(AsteroidSize::Medium), Composition::Rock.fragment_count());
+    assert_count_matches(&mut game);
+
+    // Nothing reports this despawn, the observers see it anyway
+    game.world_mut().entity_mut(rocks[1]).despawn();
+    assert_eq!(
+        game.world().resource::<AsteroidPopulation>().count(AsteroidSize::Large),
+        rocks.len() - 2
+    );
+    assert_count_matches(&mut game);
+
+    game.world_mut()
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::MainMenu);
+    game.step(1);
+    assert_eq!(*game.world().resource::<AsteroidPopulation>(), AsteroidPopulation::default());
+    assert_eq!(game.world().resource::<AsteroidPopulation>().mass(), 0.0);
+}
+
+/// Tests that budgets are filled with Large rocks first and topped up with smaller ones
+fn test_mass_budget_fills_with_the_largest_sizes() {
+    let large = AsteroidSize::Large.mass();
+    assert_eq!(fill_mass_budget(4.0 * large), vec![AsteroidSize::Large; 4]);
+    assert!(fill_mass_budget(AsteroidSize::Small.mass() * 0.5).is_empty());
+
+    let budget = 2.5 * large;
+    let sizes = fill_mass_budget(budget);
+    assert_eq!(&sizes[..2], &[AsteroidSize::Large; 2]);
+    assert!(sizes.windows(2).all(|pair| pair[0].mass() >= pair[1].mass()));
+    let mass: f32 = sizes.iter().map(|size| size.mass()).sum();
+    assert!(mass <= budget && budget - mass < AsteroidSize::Small.mass());
+
+    // Waves past the table grow by one Large rock's worth each
+    let table = WaveTable::default();
+    let last = table.waves.len() as u32;
+    let growth = table.config_for(last + 2).mass_budget - table.config_for(last).mass_budget;
+    assert!((growth - 2.0 * large).abs() < 1e-2);
+}
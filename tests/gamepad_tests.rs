@@ -0,0 +1,255 @@
+use asteroids_rust::controls::{ControlFrame, PlayerControls};
+use asteroids_rust::gamepad::{ActiveGamepads, Deadzones};
+use asteroids_rust::headless::HeadlessGame;
+use asteroids_rust::highscores::{HighScores, InitialsEntry};
+use asteroids_rust::input::parse;
+use asteroids_rust::physics::PhysicalRotation;
+use asteroids_rust::score::Score;
+use asteroids_rust::player::{GameMode, Player};
+use asteroids_rust::state::GameState;
+use bevy::prelude::*;
+
+/// The main test function that runs all other tests in order
+#[test]
+fn run_all_tests_in_order() {
+    test_deadzones_rescale();
+    test_deadzones_in_bindings_file();
+    test_hot_plugging();
+    test_start_button_starts_and_pauses();
+    test_analog_thrust();
+    test_rotation_follows_deflection();
+    test_unplugging_pauses();
+    test_two_gamepads_fly_two_ships();
+    test_mode_button_picks_two_player_games();
+    test_gamepad_enters_initials_and_leaves_game_over();
+}
+
+/// Connects a gamepad on the main menu and starts a session with it
+fn game_with_gamepad(seed: u64) -> (HeadlessGame, Gamepad) {
+    let mut game = HeadlessGame::new(seed);
+    let gamepad = game.connect_gamepad(0);
+    game.step(1);
+    game.start_game();
+    (game, gamepad)
+}
+
+/// The ship's current heading
+fn ship_rotation(game: &mut HeadlessGame) -> f32 {
+    let world = game.world_mut();
+    world.query::<&PhysicalRotation>().single(world).0
+}
+
+/// Player one's thrust axis for the latest tick
+fn thrust(game: &HeadlessGame) -> i8 {
+    game.world().resource::<PlayerControls>().get(0).thrust
+}
+
+/// Presses and lets go of a button, one tick each
+fn tap(game: &mut HeadlessGame, gamepad: Gamepad, button_type: GamepadButtonType) {
+    game.set_gamepad_button(gamepad, button_type, 1.0);
+    game.step(1);
+    game.set_gamepad_button(gamepad, button_type, 0.0);
+    game.step(1);
+}
+
+/// Which gamepad each player has
+fn slots(game: &HeadlessGame) -> [Option<Gamepad>; 2] {
+    game.world().resource::<ActiveGamepads>().0
+}
+
+/// Tests that input inside the deadzones is dropped and the rest rescaled
+fn test_deadzones_rescale() {
+    let deadzones = Deadzones { stick: 0.2, trigger: 0.1 };
+
+    assert_eq!(deadzones.apply_stick(Vec2::new(0.1, -0.1)), Vec2::ZERO);
+    assert!((deadzones.apply_stick(Vec2::new(0.6, 0.0)).x - 0.5).abs() < 1e-5);
+    assert_eq!(deadzones.apply_stick(Vec2::new(0.0, -1.0)), Vec2::new(0.0, -1.0));
+    // The deadzone is radial, so the direction of a push is kept
+    let diagonal = deadzones.apply_stick(Vec2::new(0.5, 0.5));
+    assert!((diagonal.x - diagonal.y).abs() < 1e-6);
+
+    assert_eq!(deadzones.apply_trigger(0.05), 0.0);
+    assert!((deadzones.apply_trigger(0.55) - 0.5).abs() < 1e-5);
+    assert_eq!(deadzones.apply_trigger(1.0), 1.0);
+}
+
+/// Tests that deadzones are read from the bindings file and range checked
+fn test_deadzones_in_bindings_file() {
+    let bindings = parse("asteroids-bindings v1\nstick_deadzone = 0.25\n").unwrap();
+    assert_eq!(bindings.deadzones.stick, 0.25);
+    assert_eq!(bindings.deadzones.trigger, Deadzones::default().trigger);
+
+    assert!(parse("asteroids-bindings v1\nstick_deadzone = 1.0\n").is_err());
+    assert!(parse("asteroids-bindings v1\ntrigger_deadzone = soft\n").is_err());
+}
+
+/// Tests that gamepads fill the player slots in order and spares take over freed ones
+fn test_hot_plugging() {
+    let mut game = HeadlessGame::new(1);
+    assert_eq!(slots(&game), [None, None]);
+
+    let first = game.connect_gamepad(0);
+    let second = game.connect_gamepad(1);
+    let spare = game.connect_gamepad(2);
+    game.step(1);
+    assert_eq!(slots(&game), [Some(first), Some(second)]);
+
+    game.disconnect_gamepad(first);
+    game.step(1);
+    assert_eq!(slots(&game), [Some(spare), Some(second)]);
+
+    game.disconnect_gamepad(spare);
+    game.disconnect_gamepad(second);
+    game.step(1);
+    assert_eq!(slots(&game), [None, None]);
+}
+
+/// Tests that Start leaves the main menu and then pauses the session
+fn test_start_button_starts_and_pauses() {
+    let mut game = HeadlessGame::new(2);
+    let gamepad = game.connect_gamepad(0);
+    game.step(1);
+
+    game.set_gamepad_button(gamepad, GamepadButtonType::Start, 1.0);
+    game.step(2);
+    assert_eq!(game.state(), GameState::Playing);
+
+    game.set_gamepad_button(gamepad, GamepadButtonType::Start, 0.0);
+    game.step(1);
+    game.set_gamepad_button(gamepad, GamepadButtonType::Start, 1.0);
+    game.step(2);
+    assert_eq!(game.state(), GameState::Paused);
+}
+
+/// Tests that the stick and triggers give proportional thrust
+fn test_analog_thrust() {
+    let (mut game, gamepad) = game_with_gamepad(3);
+    let deadzones = Deadzones::default();
+
+    // Resting slightly off center stays inside the deadzone
+    game.move_gamepad_axis(gamepad, GamepadAxisType::LeftStickY, 0.1);
+    game.step(1);
+    assert_eq!(thrust(&game), 0);
+
+    game.move_gamepad_axis(gamepad, GamepadAxisType::LeftStickY, 0.5);
+    game.step(1);
+    let expected = (deadzones.apply_stick(Vec2::new(0.0, 0.5)).y * 127.0).round() as i8;
+    assert_eq!(thrust(&game), expected);
+    assert!(expected > 0 && expected < ControlFrame::AXIS_MAX);
+
+    // The left trigger brakes
+    game.move_gamepad_axis(gamepad, GamepadAxisType::LeftStickY, 0.0);
+    game.set_gamepad_button(gamepad, GamepadButtonType::LeftTrigger2, 1.0);
+    game.step(1);
+    assert_eq!(thrust(&game), -ControlFrame::AXIS_MAX);
+}
+
+/// Tests that the ship turns faster the further the stick is pushed
+fn test_rotation_follows_deflection() {
+    let deadzones = Deadzones::default();
+    // Half of the stick's usable travel past the deadzone
+    let half = deadzones.stick + 0.5 * (1.0 - deadzones.stick);
+
+    let mut turned = Vec::new();
+    for deflection in [1.0, half] {
+        let (mut game, gamepad) = game_with_gamepad(4);
+        game.move_gamepad_axis(gamepad, GamepadAxisType::LeftStickX, deflection);
+        game.step(1);
+        let before = ship_rotation(&mut game);
+        game.step(20);
+        turned.push(ship_rotation(&mut game) - before);
+    }
+
+    // Pushing right turns clockwise, and a half push turns about half as fast
+    assert!(turned[0] < 0.0);
+    let ratio = turned[1] / turned[0];
+    assert!((ratio - 0.5).abs() < 0.01, "ratio was {ratio}");
+}
+
+/// Tests that losing the only gamepad mid-session pauses the game
+fn test_unplugging_pauses() {
+    let (mut game, gamepad) = game_with_gamepad(5);
+    game.disconnect_gamepad(gamepad);
+    game.step(2);
+
+    assert_eq!(game.state(), GameState::Paused);
+    assert_eq!(slots(&game), [None, None]);
+}
+
+/// Tests that in two-player games each gamepad only steers its own player's ship
+fn test_two_gamepads_fly_two_ships() {
+    let mut game = HeadlessGame::new(6);
+    let first = game.connect_gamepad(0);
+    let second = game.connect_gamepad(1);
+    game.step(1);
+    game.start_game_with(GameMode::Coop);
+
+    game.move_gamepad_axis(first, GamepadAxisType::LeftStickY, 1.0);
+    game.move_gamepad_axis(second, GamepadAxisType::LeftStickX, -1.0);
+    game.step(1);
+
+    let controls = *game.world().resource::<PlayerControls>();
+    assert_eq!(controls.get(0).thrust, ControlFrame::AXIS_MAX);
+    assert_eq!(controls.get(0).rotate, 0);
+    assert_eq!(controls.get(1).thrust, 0);
+    assert_eq!(controls.get(1).rotate, ControlFrame::AXIS_MAX);
+}
+
+/// Tests that a gamepad alone can pick and start co-op and versus from the main menu
+fn test_mode_button_picks_two_player_games() {
+    let mut game = HeadlessGame::new(7);
+    let gamepad = game.connect_gamepad(0);
+    game.step(1);
+
+    game.set_gamepad_button(gamepad, GamepadButtonType::North, 1.0);
+    game.step(1);
+    assert_eq!(*game.world().resource::<GameMode>(), GameMode::Coop);
+    assert_eq!(game.state(), GameState::MainMenu);
+
+    game.set_gamepad_button(gamepad, GamepadButtonType::North, 0.0);
+    game.step(1);
+    game.set_gamepad_button(gamepad, GamepadButtonType::Start, 1.0);
+    game.step(2);
+    assert_eq!(game.state(), GameState::Playing);
+    assert_eq!(*game.world().resource::<GameMode>(), GameMode::Coop);
+    let world = game.world_mut();
+    assert_eq!(world.query::<&Player>().iter(world).count(), 2);
+
+    // Each press moves one mode on, wrapping back round to one player
+    assert_eq!(GameMode::Coop.next(), GameMode::Versus);
+    assert_eq!(GameMode::Versus.next(), GameMode::Single);
+}
+
+/// Tests that a gamepad alone can type initials after a high score and get back to the menu
+fn test_gamepad_enters_initials_and_leaves_game_over() {
+    let (mut game, gamepad) = game_with_gamepad(8);
+    let world = game.world_mut();
+    world.resource_mut::<Score>().points[0] = 500;
+    world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameOver);
+    game.step(1);
+    assert_eq!(game.state(), GameState::GameOver);
+
+    let letters = |game: &HeadlessGame| game.world().resource::<InitialsEntry>().letters.clone();
+    // Nothing typed yet, so Start has nothing to confirm
+    tap(&mut game, gamepad, GamepadButtonType::Start);
+    assert_eq!(letters(&game), "");
+
+    tap(&mut game, gamepad, GamepadButtonType::DPadUp);
+    tap(&mut game, gamepad, GamepadButtonType::DPadUp);
+    assert_eq!(letters(&game), "B");
+    tap(&mut game, gamepad, GamepadButtonType::DPadRight);
+    tap(&mut game, gamepad, GamepadButtonType::DPadDown);
+    assert_eq!(letters(&game), "BZ");
+    tap(&mut game, gamepad, GamepadButtonType::DPadLeft);
+    assert_eq!(letters(&game), "B");
+
+    tap(&mut game, gamepad, GamepadButtonType::Start);
+    assert!(game.world().get_resource::<InitialsEntry>().is_none());
+    assert_eq!(game.world().resource::<HighScores>().entries[0].initials, "B");
+    assert_eq!(game.state(), GameState::GameOver);
+
+    tap(&mut game, gamepad, GamepadButtonType::Start);
+    assert_eq!(game.state(), GameState::MainMenu);
+}
@@ -0,0 +1,412 @@
+use asteroids_rust::asteroids::Asteroid;
+use asteroids_rust::bounds::WorldBounds;
+use asteroids_rust::headless::HeadlessGame;
+use asteroids_rust::highscores::HighScores;
+use asteroids_rust::controls::{ControlFrame, PlayerControls};
+use asteroids_rust::input::{Action, InputBindings};
+use asteroids_rust::physics::PhysicalRotation;
+use asteroids_rust::player::{
+    GameMode, Lives, Name, Player, RESPAWN_DELAY, STARTING_LIVES, VERSUS_KILL_POINTS,
+};
+use asteroids_rust::replay::{Replay, ReplayError};
+use asteroids_rust::score::Score;
+use asteroids_rust::state::GameState;
+use asteroids_rust::waves::{Wave, WaveTable, fill_mass_budget};
+use asteroids_rust::weapons::{Bullet, Faction, SingleShot, Weapon};
+use avian2d::prelude::{CollisionStarted, Position};
+use bevy::prelude::*;
+
+/// The main test function that runs all other tests in order
+#[test]
+fn run_all_tests_in_order() {
+    test_starts_on_main_menu();
+    test_player_files_are_left_alone();
+    test_start_game_spawns_ship();
+    test_first_wave_spawns_after_intermission();
+    test_same_seed_same_asteroids();
+    test_space_fires_bullet();
+    test_escape_pauses();
+    test_rebound_fire_key_shoots();
+    test_replay_file_round_trip();
+    test_replay_resimulates_session();
+    test_replay_ignores_frame_timing();
+    test_coop_spawns_two_ships();
+    test_second_player_keys_steer_their_ship();
+    test_versus_bullets_hit_the_other_ship();
+    test_two_player_replay_round_trip();
+    test_single_player_replays_still_load();
+}
+
+/// Collects the positions of every asteroid, in spawn order
+fn asteroid_positions(game: &mut HeadlessGame) -> Vec<Vec2> {
+    let world = game.world_mut();
+    let mut query = world.query_filtered::<(Entity, &Transform), With<Asteroid>>();
+    let mut asteroids: Vec<_> = query.iter(world).collect();
+    asteroids.sort_by_key(|(entity, _)| *entity);
+    asteroids
+        .into_iter()
+        .map(|(_, transform)| transform.translation.truncate())
+        .collect()
+}
+
+/// Tests that a fresh headless game waits on the main menu
+fn test_starts_on_main_menu() {
+    let mut game = HeadlessGame::new(1);
+    game.step(10);
+    assert_eq!(game.state(), GameState::MainMenu);
+    assert!(asteroid_positions(&mut game).is_empty());
+}
+
+/// Tests that the high scores and key bindings are in-memory defaults with no file behind them
+fn test_player_files_are_left_alone() {
+    let game = HeadlessGame::new(0);
+    let high_scores = game.world().resource::<HighScores>();
+    assert!(high_scores.path.is_none());
+    assert!(high_scores.entries.is_empty());
+    let bindings = game.world().resource::<InputBindings>();
+    assert!(bindings.path.is_none());
+    assert_eq!(bindings.key(Action::Fire), InputBindings::default().key(Action::Fire));
+}
+
+/// Tests that starting a game puts a ship in play with full lives
+fn test_start_game_spawns_ship() {
+    let mut game = HeadlessGame::new(1);
+    game.start_game();
+
+    assert_eq!(game.state(), GameState::Playing);
+    let world = game.world_mut();
+    assert_eq!(world.query::<&Name>().iter(world).count(), 1);
+    assert_eq!(world.resource::<Lives>().remaining, [STARTING_LIVES, 0]);
+}
+
+/// Tests that the first wave appears once the intermission is over
+fn test_first_wave_spawns_after_intermission() {
+    let mut game = HeadlessGame::new(2);
+    game.start_game();
+    game.step_until_first_wave();
+
+    let budget = game.world().resource::<WaveTable>().config_for(1).mass_budget;
+    let expected = fill_mass_budget(budget).len();
+    assert_eq!(game.world().resource::<Wave>().number, 1);
+    assert_eq!(asteroid_positions(&mut game).len(), expected);
+}
+
+/// Tests that two games with the same seed lay out the same field
+fn test_same_seed_same_asteroids() {
+    let mut first = HeadlessGame::new(42);
+    let mut second = HeadlessGame::new(42);
+    for game in [&mut first, &mut second] {
+        game.start_game();
+        game.step_until_first_wave();
+    }
+
+    let first_positions = asteroid_positions(&mut first);
+    assert!(!first_positions.is_empty());
+    assert_eq!(first_positions, asteroid_positions(&mut second));
+}
+
+/// Tests that holding Space fires a bullet from the ship
+fn test_space_fires_bullet() {
+    let mut game = HeadlessGame::new(3);
+    game.start_game();
+    game.press(KeyCode::Space);
+    // The first shot waits for the fire cooldown to run out
+    let cooldown = SingleShot.fire_interval();
+    let ticks = (cooldown / game.tick_duration().as_secs_f32()).ceil() as u32 + 1;
+    game.step(ticks);
+
+    let world = game.world_mut();
+    assert!(world.query::<&Bullet>().iter(world).count() >= 1);
+}
+
+/// Tests that Escape pauses a running game
+fn test_escape_pauses() {
+    let mut game = HeadlessGame::new(4);
+    game.start_game();
+    game.press(KeyCode::Escape);
+    game.step(2);
+
+    assert_eq!(game.state(), GameState::Paused);
+}
+
+/// Tests that gameplay follows the bindings rather than fixed keys
+fn test_rebound_fire_key_shoots() {
+    let mut game = HeadlessGame::new(5);
+    game.world_mut()
+        .resource_mut::<InputBindings>()
+        .bind(Action::Fire, KeyCode::KeyF)
+        .unwrap();
+    game.start_game();
+    let cooldown = SingleShot.fire_interval();
+    let ticks = (cooldown / game.tick_duration().as_secs_f32()).ceil() as u32 + 1;
+
+    // Space no longer fires
+    game.press(KeyCode::Space);
+    game.step(ticks);
+    let world = game.world_mut();
+    assert_eq!(world.query::<&Bullet>().iter(world).count(), 0);
+
+    game.release(KeyCode::Space);
+    game.press(KeyCode::KeyF);
+    game.step(2);
+    let world = game.world_mut();
+    assert_eq!(world.query::<&Bullet>().iter(world).count(), 1);
+}
+
+/// Tests that replays survive encoding and reject damaged files
+fn test_replay_file_round_trip() {
+    let mut replay = Replay::new(99, GameMode::Single, WorldBounds::new(800.0, 600.0));
+    let thrust = ControlFrame { thrust: ControlFrame::AXIS_MAX, rotate: 0, buttons: 0 };
+    let fire = ControlFrame { thrust: 0, rotate: -40, buttons: ControlFrame::FIRE };
+    replay.frames.extend(std::iter::repeat_n(one_player(thrust), 300));
+    replay.frames.push(one_player(fire));
+    replay.frames.extend(std::iter::repeat_n(PlayerControls::default(), 70000));
+
+    let bytes = replay.encode();
+    // Long runs collapse, so the file stays far smaller than one frame per tick
+    assert!(bytes.len() < 100);
+    assert_eq!(Replay::decode(&bytes).unwrap(), replay);
+
+    assert!(matches!(Replay::decode(b"nope"), Err(ReplayError::NotAReplay)));
+    assert!(matches!(
+        Replay::decode(&bytes[..bytes.len() - 2]),
+        Err(ReplayError::Truncated)
+    ));
+
+    // An empty replay ends on its tick count, so hand-made runs can follow it
+    let header = Replay::new(1, GameMode::Single, WorldBounds::new(800.0, 600.0)).encode();
+    let with_count = |count: u32, runs: &[[u8; 5]]| {
+        let mut bytes = header[..header.len() - 4].to_vec();
+        bytes.extend_from_slice(&count.to_le_bytes());
+        runs.iter().for_each(|run| bytes.extend_from_slice(run));
+        bytes
+    };
+    let idle = |length: u16| {
+        let [low, high] = length.to_le_bytes();
+        [low, high, 0, 0, 0]
+    };
+    assert_eq!(Replay::decode(&with_count(3, &[idle(3)])).unwrap().frames.len(), 3);
+    // A count far beyond what the runs could hold is refused without reserving room for it
+    assert!(matches!(
+        Replay::decode(&with_count(u32::MAX, &[idle(3)])),
+        Err(ReplayError::Truncated)
+    ));
+    assert!(matches!(
+        Replay::decode(&with_count(3, &[idle(0), idle(3)])),
+        Err(ReplayError::Corrupt)
+    ));
+    assert!(matches!(
+        Replay::decode(&with_count(3, &[idle(2), idle(2)])),
+        Err(ReplayError::Corrupt)
+    ));
+}
+
+/// Controls for a tick where only player one does anything
+fn one_player(frame: ControlFrame) -> PlayerControls {
+    PlayerControls([frame, ControlFrame::default()])
+}
+
+/// Snapshot of the state a replay has to reproduce
+fn session_snapshot(game: &mut HeadlessGame) -> (Vec<Vec2>, Vec<f32>, Vec<Vec2>, [u32; 2]) {
+    let asteroids = asteroid_positions(game);
+    let world = game.world_mut();
+    let rotations = world
+        .query::<&PhysicalRotation>()
+        .iter(world)
+        .map(|rotation| rotation.0)
+        .collect();
+    let mut bullets: Vec<Vec2> = world
+        .query_filtered::<&Position, With<Bullet>>()
+        .iter(world)
+        .map(|position| position.0)
+        .collect();
+    bullets.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    let score = world.resource::<Score>().points;
+    (asteroids, rotations, bullets, score)
+}
+
+/// Tests that playing back a recorded session ends in the same state
+fn test_replay_resimulates_session() {
+    let mut live = HeadlessGame::new(7);
+    live.record();
+    live.start_game();
+    let ticks = live.ticks_until_first_wave();
+    live.press(KeyCode::KeyA);
+    live.press(KeyCode::Space);
+    live.step(ticks);
+    live.release(KeyCode::KeyA);
+    live.press(KeyCode::KeyW);
+    live.step(60);
+    live.release(KeyCode::Space);
+    live.press(KeyCode::KeyR);
+    live.step(30);
+
+    let replay = live.recording().unwrap().clone();
+    assert_eq!(replay.seed, 7);
+    assert_eq!(replay.frames.len() as u32, ticks + 91);
+
+    let mut replayed = HeadlessGame::from_replay(Replay::decode(&replay.encode()).unwrap());
+    assert_eq!(replayed.state(), GameState::Playing);
+    replayed.step(replay.frames.len() as u32);
+
+    assert_eq!(session_snapshot(&mut replayed), session_snapshot(&mut live));
+
+    // Running out of recorded input hands the game back to the menu
+    replayed.step(2);
+    assert_eq!(replayed.state(), GameState::MainMenu);
+}
+
+/// Tests that a session recorded between frame boundaries replays the same shots
+fn test_replay_ignores_frame_timing() {
+    let mut live = HeadlessGame::new(12);
+    live.record();
+    live.start_game();
+    // Rendered transforms now trail the simulation by half a tick
+    live.step_with_overstep(0.5);
+    live.press(KeyCode::KeyA);
+    live.press(KeyCode::Space);
+    live.step(40);
+
+    let replay = live.recording().unwrap().clone();
+    let mut replayed = HeadlessGame::from_replay(replay.clone());
+    replayed.step(replay.frames.len() as u32);
+
+    let snapshot = session_snapshot(&mut live);
+    assert!(!snapshot.2.is_empty());
+    assert_eq!(session_snapshot(&mut replayed), snapshot);
+}
+
+/// Each ship's heading, in player order
+fn ship_rotations(game: &mut HeadlessGame) -> Vec<f32> {
+    let world = game.world_mut();
+    let mut ships: Vec<_> = world
+        .query::<(&Player, &PhysicalRotation)>()
+        .iter(world)
+        .map(|(player, rotation)| (player.id, rotation.0))
+        .collect();
+    ships.sort_by_key(|(id, _)| *id);
+    ships.into_iter().map(|(_, rotation)| rotation).collect()
+}
+
+/// The ship flown by the given player, if it is in play
+fn ship_of(game: &mut HeadlessGame, id: usize) -> Option<Entity> {
+    let world = game.world_mut();
+    world
+        .query::<(Entity, &Player)>()
+        .iter(world)
+        .find(|(_, player)| player.id == id)
+        .map(|(entity, _)| entity)
+}
+
+/// Tests that co-op puts a ship in play for each player, apart and with their own lives and score
+fn test_coop_spawns_two_ships() {
+    let mut game = HeadlessGame::new(8);
+    game.start_game_with(GameMode::Coop);
+
+    let world = game.world_mut();
+    let mut spawns: Vec<Vec2> = world
+        .query_filtered::<&Transform, With<Player>>()
+        .iter(world)
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    spawns.sort_by(|a, b| a.x.total_cmp(&b.x));
+    assert_eq!(spawns.len(), 2);
+    assert!(spawns[0].distance(spawns[1]) > 100.0);
+    assert_eq!(world.resource::<Lives>().remaining, [STARTING_LIVES; 2]);
+    assert_eq!(world.resource::<Score>().points, [0, 0]);
+}
+
+/// Tests that player two's default keys turn only their own ship
+fn test_second_player_keys_steer_their_ship() {
+    let mut game = HeadlessGame::new(9);
+    game.start_game_with(GameMode::Coop);
+    game.press(KeyCode::ArrowLeft);
+    game.step(10);
+
+    let rotations = ship_rotations(&mut game);
+    assert_eq!(rotations[0], 0.0);
+    assert!(rotations[1] > 0.0);
+
+    // Player two's keys do nothing in a one-player game
+    let mut single = HeadlessGame::new(9);
+    single.start_game();
+    single.press(KeyCode::ArrowLeft);
+    single.step(10);
+    assert_eq!(ship_rotations(&mut single), vec![0.0]);
+}
+
+/// Tests that in versus a bullet destroys the other player's ship but not its shooter's
+fn test_versus_bullets_hit_the_other_ship() {
+    let mut game = HeadlessGame::new(10);
+    game.start_game_with(GameMode::Versus);
+    game.world_mut().resource_mut::<Lives>().remaining = [1, 1];
+    let first = ship_of(&mut game, 0).unwrap();
+    let second = ship_of(&mut game, 1).unwrap();
+
+    // A player's own bullet passes through their ship
+    let own = game.world_mut().spawn((Bullet::default(), Faction::Player(0))).id();
+    game.world_mut().send_event(CollisionStarted(first, own));
+    game.step(2);
+    assert_eq!(ship_of(&mut game, 0), Some(first));
+
+    game.world_mut().send_event(CollisionStarted(second, own));
+    game.step(2);
+    assert_eq!(ship_of(&mut game, 1), None);
+    assert_eq!(game.world().resource::<Lives>().remaining, [1, 0]);
+    assert_eq!(game.world().resource::<Score>().points, [VERSUS_KILL_POINTS, 0]);
+
+    // Player two is out, but the game goes on while player one still flies
+    let respawn_ticks = (RESPAWN_DELAY / game.tick_duration().as_secs_f32()).ceil() as u32 + 2;
+    game.step(respawn_ticks);
+    assert_eq!(game.state(), GameState::Playing);
+    assert_eq!(ship_of(&mut game, 1), None);
+
+    let stray = game.world_mut().spawn((Bullet::default(), Faction::Player(1))).id();
+    game.world_mut().send_event(CollisionStarted(stray, first));
+    game.step(respawn_ticks);
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+/// Tests that two-player replays keep both players' input and the mode
+fn test_two_player_replay_round_trip() {
+    let mut replay = Replay::new(11, GameMode::Coop, WorldBounds::new(800.0, 600.0));
+    let thrust = ControlFrame { thrust: ControlFrame::AXIS_MAX, rotate: 0, buttons: 0 };
+    let fire = ControlFrame { thrust: 0, rotate: 0, buttons: ControlFrame::FIRE };
+    replay.frames.extend(std::iter::repeat_n(PlayerControls([thrust, fire]), 50));
+    replay.frames.extend(std::iter::repeat_n(PlayerControls([fire, fire]), 50));
+
+    let decoded = Replay::decode(&replay.encode()).unwrap();
+    assert_eq!(decoded.mode, GameMode::Coop);
+    assert_eq!(decoded, replay);
+
+    // Replays record their mode, so playback sets it up again
+    let mut live = HeadlessGame::new(12);
+    live.record();
+    live.start_game_with(GameMode::Versus);
+    live.press(KeyCode::KeyA);
+    live.press(KeyCode::ArrowRight);
+    live.step(20);
+    let replay = live.recording().unwrap().clone();
+    assert_eq!(replay.mode, GameMode::Versus);
+
+    let mut replayed = HeadlessGame::from_replay(replay.clone());
+    replayed.step(replay.frames.len() as u32);
+    assert_eq!(ship_rotations(&mut replayed), ship_rotations(&mut live));
+}
+
+/// Tests that replays written before two-player mode load as one-player sessions
+fn test_single_player_replays_still_load() {
+    let mut replay = Replay::new(13, GameMode::Single, WorldBounds::new(800.0, 600.0));
+    let turn = ControlFrame { thrust: 0, rotate: 60, buttons: 0 };
+    replay.frames.extend(std::iter::repeat_n(one_player(turn), 40));
+
+    // The first format had version 1 and no mode byte, but was otherwise the same
+    let mut bytes = replay.encode();
+    bytes[4] = 1;
+    bytes.remove(5);
+    assert_eq!(Replay::decode(&bytes).unwrap(), replay);
+
+    bytes[4] = 2;
+    bytes.insert(5, 9);
+    assert!(matches!(Replay::decode(&bytes), Err(ReplayError::UnknownMode(9))));
+}
@@ -0,0 +1,67 @@
+use asteroids_rust::highscores::{
+    HighScoreEntry, HighScoreError, HighScores, MAX_HIGH_SCORES, parse, serialize,
+};
+
+fn entry(score: u32, initials: &str) -> HighScoreEntry {
+    HighScoreEntry {
+        score,
+        initials: initials.to_string(),
+        timestamp: 1_700_000_000,
+        wave: 3,
+    }
+}
+
+/// The main test function that runs all other tests in order
+#[test]
+fn run_all_tests_in_order() {
+    test_round_trip();
+    test_insert_keeps_rank_order_and_capacity();
+    test_corrupted_files_are_rejected();
+    test_entry_date_formatting();
+}
+
+/// Tests that serialized tables parse back to the same entries
+fn test_round_trip() {
+    let entries = vec![entry(900, "AAA"), entry(450, "BB"), entry(20, "C")];
+    let parsed = parse(&serialize(&entries)).expect("serialized table should parse");
+    assert_eq!(parsed, entries);
+}
+
+/// Tests that inserts land in rank order and the table never grows past its cap
+fn test_insert_keeps_rank_order_and_capacity() {
+    let mut high_scores = HighScores::default();
+    for score in 1..=MAX_HIGH_SCORES as u32 {
+        high_scores.insert(entry(score * 100, "AAA"));
+    }
+    assert_eq!(high_scores.entries.len(), MAX_HIGH_SCORES);
+    assert!(!high_scores.qualifies(100));
+
+    assert_eq!(high_scores.insert(entry(550, "NEW")), Some(5));
+    assert_eq!(high_scores.entries.len(), MAX_HIGH_SCORES);
+    assert_eq!(high_scores.entries.last().unwrap().score, 200);
+    assert_eq!(high_scores.insert(entry(50, "LOW")), None);
+}
+
+/// Tests that damaged or foreign files are reported instead of half-loaded
+fn test_corrupted_files_are_rejected() {
+    assert!(matches!(parse(""), Err(HighScoreError::MissingHeader)));
+    assert!(matches!(parse("garbage\n"), Err(HighScoreError::MissingHeader)));
+    assert!(matches!(
+        parse("asteroids-highscores v99\n"),
+        Err(HighScoreError::UnsupportedVersion(99))
+    ));
+    assert!(matches!(
+        parse("asteroids-highscores v1\n100\tAAA\t0\t1\nnot a row\n"),
+        Err(HighScoreError::Malformed(3))
+    ));
+}
+
+/// Tests that entry timestamps are shown as calendar dates
+fn test_entry_date_formatting() {
+    assert_eq!(entry(1, "A").date(), "2023-11-14");
+    let epoch = HighScoreEntry {
+        timestamp: 0,
+        ..entry(1, "A")
+    };
+    assert_eq!(epoch.date(), "1970-01-01");
+}
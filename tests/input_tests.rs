@@ -0,0 +1,193 @@
+use asteroids_rust::input::{
+    Action, Binding, BindingsError, InputBindings, UNBOUND, bound_key_name, key_name, parse,
+    parse_key, serialize,
+};
+use bevy::prelude::KeyCode;
+
+/// The main test function that runs all other tests in order
+#[test]
+fn run_all_tests_in_order() {
+    test_defaults_have_no_conflicts();
+    test_round_trip();
+    test_partial_file_keeps_defaults();
+    test_bad_files_are_rejected();
+    test_bind_refuses_conflicts_and_reserved_keys();
+    test_key_names_round_trip();
+    test_second_player_bindings();
+    test_file_keys_displace_defaults();
+    test_player_one_on_the_arrows_still_loads();
+    test_switch_weapon_keys_give_way_to_saved_files();
+}
+
+/// Tests that the out-of-the-box bindings are valid, with wireframes off Space
+fn test_defaults_have_no_conflicts() {
+    let bindings = InputBindings::default();
+    assert!(bindings.check().is_ok());
+    assert_eq!(bindings.key(Action::Fire), Some(KeyCode::Space));
+    assert_eq!(bindings.key(Action::DebugWireframe), Some(KeyCode::Tab));
+}
+
+/// Tests that serialized bindings parse back to the same keys
+fn test_round_trip() {
+    let mut bindings = InputBindings::default();
+    bindings.bind(Action::Thrust, KeyCode::KeyI).unwrap();
+    bindings.bind(Action::Fire, KeyCode::ControlLeft).unwrap();
+    bindings.bind(Binding::new(1, Action::Fire), KeyCode::Slash).unwrap();
+
+    let parsed = parse(&serialize(&bindings)).expect("serialized bindings should parse");
+    for binding in Binding::all() {
+        assert_eq!(parsed.key(binding), bindings.key(binding));
+    }
+}
+
+/// Tests that actions missing from a file fall back to their default keys
+fn test_partial_file_keeps_defaults() {
+    let parsed = parse("asteroids-bindings v1\n# flying with IJKL\nthrust = I\n\n").unwrap();
+    assert_eq!(parsed.key(Action::Thrust), Some(KeyCode::KeyI));
+    assert_eq!(parsed.key(Action::Fire), Some(Action::Fire.default_key()));
+}
+
+/// Tests that damaged, conflicting or reserved bindings files are refused
+fn test_bad_files_are_rejected() {
+    assert!(matches!(parse(""), Err(BindingsError::MissingHeader)));
+    assert!(matches!(
+        parse("asteroids-bindings v9\n"),
+        Err(BindingsError::UnsupportedVersion(9))
+    ));
+    assert!(matches!(
+        parse("asteroids-bindings v1\nthrust = NotAKey\n"),
+        Err(BindingsError::Malformed(2))
+    ));
+    assert!(matches!(
+        parse("asteroids-bindings v1\nwarp = W\n"),
+        Err(BindingsError::Malformed(2))
+    ));
+    // Two lines of the same file giving out one key clash
+    assert!(matches!(
+        parse("asteroids-bindings v1\nthrust = W\nfire = W\n"),
+        Err(BindingsError::Conflict(first, second, KeyCode::KeyW))
+            if first == Action::Thrust.into() && second == Action::Fire.into()
+    ));
+    assert!(matches!(
+        parse("asteroids-bindings v1\npause = Enter\n"),
+        Err(BindingsError::Reserved(binding, KeyCode::Enter)) if binding == Action::Pause.into()
+    ));
+}
+
+/// Tests that binding a taken or reserved key fails and changes nothing
+fn test_bind_refuses_conflicts_and_reserved_keys() {
+    let mut bindings = InputBindings::default();
+
+    assert!(matches!(
+        bindings.bind(Action::Fire, KeyCode::KeyW),
+        Err(BindingsError::Conflict(first, second, KeyCode::KeyW))
+            if first == Action::Thrust.into() && second == Action::Fire.into()
+    ));
+    assert!(matches!(
+        bindings.bind(Action::Fire, KeyCode::KeyQ),
+        Err(BindingsError::Reserved(binding, KeyCode::KeyQ)) if binding == Action::Fire.into()
+    ));
+    assert_eq!(bindings.key(Action::Fire), Some(KeyCode::Space));
+
+    // Rebinding an action to its own key is not a conflict
+    assert!(bindings.bind(Action::Fire, KeyCode::Space).is_ok());
+
+    bindings.bind(Action::Fire, KeyCode::KeyF).unwrap();
+    bindings.reset();
+    assert_eq!(bindings.key(Action::Fire), Some(KeyCode::Space));
+}
+
+/// Tests that key names are stable in both directions
+fn test_key_names_round_trip() {
+    for key in [KeyCode::KeyW, KeyCode::ShiftLeft, KeyCode::ArrowLeft, KeyCode::Digit5] {
+        assert_eq!(parse_key(key_name(key)), Some(key));
+    }
+    assert_eq!(parse_key("leftshift"), Some(KeyCode::ShiftLeft));
+    assert_eq!(parse_key("Hyperdrive"), None);
+}
+
+/// Tests that player two has their own keys, while shared actions keep a single one
+fn test_second_player_bindings() {
+    let mut bindings = InputBindings::default();
+    let p2_fire = Binding::new(1, Action::Fire);
+    assert_eq!(bindings.key(p2_fire), Some(KeyCode::ControlRight));
+    assert_eq!(bindings.key(Binding::new(1, Action::Thrust)), Some(KeyCode::ArrowUp));
+    assert_eq!(Binding::new(1, Action::Pause), Binding::from(Action::Pause));
+
+    // The players cannot share a key
+    assert!(matches!(
+        bindings.bind(p2_fire, KeyCode::Space),
+        Err(BindingsError::Conflict(first, second, KeyCode::Space))
+            if first == Action::Fire.into() && second == p2_fire
+    ));
+
+    let parsed = parse("asteroids-bindings v1\np2.fire = Slash\n").unwrap();
+    assert_eq!(parsed.key(p2_fire), Some(KeyCode::Slash));
+    assert_eq!(parsed.key(Action::Fire), Some(KeyCode::Space));
+    assert_eq!(Binding::from_id(&p2_fire.id()), Some(p2_fire));
+    bindings.bind(p2_fire, KeyCode::Slash).unwrap();
+    assert_eq!(bindings.binding_for(KeyCode::Slash), Some(p2_fire));
+}
+
+/// Tests that a key a file hands out is taken from the default holding it, but not from another line
+fn test_file_keys_displace_defaults() {
+    // Fire moves onto thrust's default, leaving thrust unbound
+    let parsed = parse("asteroids-bindings v1\nfire = W\n").unwrap();
+    assert_eq!(parsed.key(Action::Fire), Some(KeyCode::KeyW));
+    assert_eq!(parsed.key(Action::Thrust), None);
+    assert!(parsed.check().is_ok());
+
+    // The order of the lines makes no difference
+    let parsed = parse("asteroids-bindings v1\nfire = W\nthrust = Space\n").unwrap();
+    assert_eq!(parsed.key(Action::Fire), Some(KeyCode::KeyW));
+    assert_eq!(parsed.key(Action::Thrust), Some(KeyCode::Space));
+
+    // Unbound bindings survive a round trip and are never pressed
+    let parsed = parse("asteroids-bindings v1\nfire = W\n").unwrap();
+    let reparsed = parse(&serialize(&parsed)).unwrap();
+    assert_eq!(reparsed.key(Action::Thrust), None);
+    assert_eq!(reparsed.binding_for(KeyCode::KeyW), Some(Action::Fire.into()));
+    assert_eq!(bound_key_name(reparsed.key(Action::Thrust)), UNBOUND);
+}
+
+/// Tests that a file from before two-player mode, with player one on the arrows, loads as saved
+fn test_player_one_on_the_arrows_still_loads() {
+    let contents = "asteroids-bindings v1\n\
+        thrust = Up\n\
+        reverse = Down\n\
+        rotate_left = Left\n\
+        rotate_right = Right\n\
+        fire = RightControl\n\
+        hyperspace = RightShift\n\
+        reset_ship = Period\n\
+        pause = Escape\n\
+        debug_wireframe = Tab\n";
+    let parsed = parse(contents).expect("a file valid before player two existed should load");
+    assert_eq!(parsed.key(Action::Thrust), Some(KeyCode::ArrowUp));
+    assert_eq!(parsed.key(Action::Fire), Some(KeyCode::ControlRight));
+    assert_eq!(parsed.key(Action::ResetShip), Some(KeyCode::Period));
+
+    // Player two lost the keys player one kept, and keeps the rest of the defaults
+    for action in [Action::Thrust, Action::Reverse, Action::Fire, Action::ResetShip] {
+        assert_eq!(parsed.key(Binding::new(1, action)), None);
+    }
+    let p2_switch = Binding::new(1, Action::SwitchWeapon);
+    assert_eq!(parsed.key(p2_switch), Some(p2_switch.default_key()));
+    assert!(parsed.check().is_ok());
+}
+
+/// Tests that a file from before weapon switching, already using E and Comma, loads as saved
+fn test_switch_weapon_keys_give_way_to_saved_files() {
+    let parsed = parse("asteroids-bindings v1\nhyperspace = E\np2.hyperspace = Comma\n")
+        .expect("a file valid before weapon switching existed should load");
+    assert_eq!(parsed.key(Action::Hyperspace), Some(KeyCode::KeyE));
+    assert_eq!(parsed.key(Binding::new(1, Action::Hyperspace)), Some(KeyCode::Comma));
+    assert_eq!(parsed.key(Action::SwitchWeapon), None);
+    assert_eq!(parsed.key(Binding::new(1, Action::SwitchWeapon)), None);
+
+    // Giving the action a key again puts it back on the keyboard
+    let mut bindings = parsed;
+    bindings.bind(Action::SwitchWeapon, KeyCode::ShiftLeft).unwrap();
+    assert_eq!(bindings.binding_for(KeyCode::ShiftLeft), Some(Action::SwitchWeapon.into()));
+    assert!(bindings.check().is_ok());
+}
@@ -1,7 +1,9 @@
 use asteroids_rust::particles::{Particle, engine_particle_system};
 use asteroids_rust::physics::{
-    MAX_VELOCITY, MovementInputAccumulator, PhysicalRotation, Velocity, apply_movement,
+    MAX_VELOCITY, MovementInputAccumulator, PhysicalRotation, apply_movement,
 };
+use asteroids_rust::rng::GameRng;
+use avian2d::prelude::LinearVelocity;
 use bevy::ecs::schedule::Schedule;
 use bevy::ecs::world::World;
 use bevy::prelude::*;
@@ -57,7 +59,7 @@ fn test_apply_movement_clamp() {
     world.spawn((
         MovementInputAccumulator { value: Vec2::Y },
         PhysicalRotation(0.0),
-        Velocity(Vec3::ZERO),
+        LinearVelocity(Vec2::ZERO),
     ));
     world.insert_resource(Time::<Fixed>::default());
     {
@@ -69,7 +71,7 @@ fn test_apply_movement_clamp() {
     schedule.add_systems(apply_movement);
     schedule.run(&mut world);
 
-    let velocity = world.query::<&Velocity>().single(&world).0;
+    let velocity = world.query::<&LinearVelocity>().single(&world).0;
     let expected = MAX_VELOCITY;
     info!("Velocity after apply_movement: {}", velocity.y);
     assert!((velocity.y - expected).abs() < f32::EPSILON);
@@ -87,6 +89,7 @@ fn test_engine_particle_spawn() {
     ));
     world.insert_resource(Assets::<Mesh>::default());
     world.insert_resource(Assets::<ColorMaterial>::default());
+    world.insert_resource(GameRng::new(0));
 
     let mut schedule = Schedule::default();
     schedule.add_systems(engine_particle_system);
@@ -0,0 +1,140 @@
+use asteroids_rust::asteroids::{Asteroid, AsteroidSize, Composition};
+use asteroids_rust::bounds::{BoundingRadius, WorldBounds};
+use asteroids_rust::headless::HeadlessGame;
+use asteroids_rust::player::{Player, RESPAWN_DELAY, RespawnTimer, SHIP_RADIUS};
+use asteroids_rust::safety::{MovingCircle, SpawnSafety, closest_approach};
+use avian2d::prelude::{LinearVelocity, Position};
+use bevy::prelude::*;
+
+/// The main test function that runs all other tests in order
+#[test]
+fn run_all_tests_in_order() {
+    test_closest_approach();
+    test_closest_approach_wraps();
+    test_choose_prefers_safe_spawns();
+    test_waves_spawn_clear_of_the_ship();
+    test_respawn_avoids_a_parked_asteroid();
+}
+
+/// A circle of radius 10 at `position` moving at `velocity`
+fn circle(position: Vec2, velocity: Vec2) -> MovingCircle {
+    MovingCircle { position, velocity, radius: 10.0 }
+}
+
+/// Tests the closest approach of circles heading towards, past and away from each other
+fn test_closest_approach() {
+    let bounds = WorldBounds::new(800.0, 600.0);
+    let ship = circle(Vec2::ZERO, Vec2::ZERO);
+
+    // Head-on, reaching the ship within the lookahead
+    let incoming = circle(Vec2::new(200.0, 0.0), Vec2::new(-100.0, 0.0));
+    assert!(closest_approach(&bounds, ship, incoming, 3.0) < 1e-3);
+    // The same rock, but too slow to arrive in time
+    assert!((closest_approach(&bounds, ship, incoming, 1.0) - 100.0).abs() < 1e-3);
+
+    // Passing by 50 units to the side
+    let passing = circle(Vec2::new(200.0, 50.0), Vec2::new(-100.0, 0.0));
+    assert!((closest_approach(&bounds, ship, passing, 3.0) - 50.0).abs() < 1e-3);
+
+    // Moving away is closest right now
+    let leaving = circle(Vec2::new(200.0, 0.0), Vec2::new(100.0, 0.0));
+    assert!((closest_approach(&bounds, ship, leaving, 3.0) - 200.0).abs() < 1e-3);
+}
+
+/// Tests that a circle about to wrap around onto the other one counts as approaching
+fn test_closest_approach_wraps() {
+    let bounds = WorldBounds::new(800.0, 600.0);
+    let extents = bounds.wrap_extents();
+    let ship = circle(Vec2::new(-extents.x + 50.0, 0.0), Vec2::ZERO);
+
+    // On the far right edge, flying right, wrapping onto the ship on the left
+    let rock = circle(Vec2::new(extents.x - 100.0, 0.0), Vec2::new(100.0, 0.0));
+    assert!(closest_approach(&bounds, ship, rock, 3.0) < 1e-3);
+}
+
+/// Tests that the first safe candidate wins and the safest is used when none is safe
+fn test_choose_prefers_safe_spawns() {
+    let bounds = WorldBounds::new(800.0, 600.0);
+    let safety = SpawnSafety::default();
+    let ship = [circle(Vec2::ZERO, Vec2::ZERO)];
+
+    let candidates = [Vec2::new(30.0, 0.0), Vec2::new(300.0, 0.0), Vec2::new(350.0, 0.0)];
+    let chosen = safety.choose(&bounds, &ship, |attempt| {
+        let position = candidates[attempt as usize % candidates.len()];
+        (position, circle(position, Vec2::ZERO))
+    });
+    assert_eq!(chosen, Vec2::new(300.0, 0.0));
+    assert!(safety.is_safe(&bounds, circle(chosen, Vec2::ZERO), &ship));
+
+    let crowded = SpawnSafety { clearance: 10_000.0, max_attempts: 3, ..safety };
+    let chosen = crowded.choose(&bounds, &ship, |attempt| {
+        let position = candidates[attempt as usize];
+        (position, circle(position, Vec2::ZERO))
+    });
+    assert_eq!(chosen, Vec2::new(350.0, 0.0));
+
+    // Nothing to avoid means every spawn is safe
+    assert!(safety.is_safe(&bounds, circle(Vec2::ZERO, Vec2::ZERO), &[]));
+}
+
+/// Tests that no asteroid of the first wave is on course to reach the ship
+fn test_waves_spawn_clear_of_the_ship() {
+    for seed in 0..20 {
+        let mut game = HeadlessGame::new(seed);
+        game.start_game();
+        game.step_until_first_wave();
+
+        let bounds = *game.world().resource::<WorldBounds>();
+        // Physics may already have moved the asteroids by up to two ticks since they were checked
+        let mut safety = *game.world().resource::<SpawnSafety>();
+        safety.lookahead -= 2.0 * game.tick_duration().as_secs_f32();
+        let world = game.world_mut();
+        let ship = world
+            .query_filtered::<(&Position, &BoundingRadius), With<Player>>()
+            .single(world);
+        let ship = [MovingCircle { position: ship.0.0, velocity: Vec2::ZERO, radius: ship.1.0 }];
+
+        // Measured by the farthest corner of each rock's outline, not its mean radius
+        let asteroids: Vec<MovingCircle> = world
+            .query_filtered::<(&Position, &LinearVelocity, &BoundingRadius), With<Asteroid>>()
+            .iter(world)
+            .map(|(position, velocity, radius)| MovingCircle {
+                position: position.0,
+                velocity: velocity.0,
+                radius: radius.0,
+            })
+            .collect();
+        assert!(!asteroids.is_empty());
+        for asteroid in asteroids {
+            assert!(safety.is_safe(&bounds, asteroid, &ship), "seed {seed}: {asteroid:?}");
+        }
+    }
+}
+
+/// Tests that a ship respawns away from an asteroid sitting on its spawn point
+fn test_respawn_avoids_a_parked_asteroid() {
+    let mut game = HeadlessGame::new(21);
+    game.start_game();
+
+    let world = game.world_mut();
+    let ship = world.query_filtered::<Entity, With<Player>>().single(world);
+    world.entity_mut(ship).despawn();
+    world.spawn(RespawnTimer::new(Player::new(0)));
+    let radius = AsteroidSize::Large.radius();
+    world.spawn((
+        Asteroid::new(AsteroidSize::Large, Composition::Rock),
+        Position(Vec2::ZERO),
+        LinearVelocity(Vec2::ZERO),
+        BoundingRadius(radius),
+    ));
+
+    let ticks = (RESPAWN_DELAY / game.tick_duration().as_secs_f32()).ceil() as u32 + 1;
+    game.step(ticks);
+
+    let clearance = game.world().resource::<SpawnSafety>().clearance;
+    let world = game.world_mut();
+    let position = world
+        .query_filtered::<&Position, With<Player>>()
+        .single(world);
+    assert!(position.0.length() >= radius + SHIP_RADIUS + clearance);
+}
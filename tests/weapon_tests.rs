@@ -0,0 +1,487 @@
+use asteroids_rust::asteroids::{Asteroid, AsteroidSize, Composition};
+use asteroids_rust::bounds::{BoundingRadius, WorldBounds};
+use asteroids_rust::headless::HeadlessGame;
+use asteroids_rust::laser::{
+    BeamVisual, ENERGY_CAPACITY, ENERGY_RECHARGE, ENERGY_RESTART_FRACTION, Energy, LaserBeam,
+};
+use asteroids_rust::missiles::{Homing, Missile, MissileLauncher, acquire_target, steer};
+use asteroids_rust::particles::Particle;
+use asteroids_rust::physics::PhysicalRotation;
+use asteroids_rust::player::Player;
+use asteroids_rust::saucers::{Saucer, SaucerSize};
+use asteroids_rust::score::Score;
+use asteroids_rust::weapons::{
+    Bullet, Faction, Loadout, MUZZLE_DISTANCE, Projectile, RailShot, RapidCannon, SingleShot,
+    Spread, SpreadShot, Weapon,
+};
+use avian2d::prelude::{
+    AngularVelocity, Collider, ColliderDensity, CollisionStarted, LinearVelocity, Position,
+};
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// The main test function that runs all other tests in order
+#[test]
+fn run_all_tests_in_order() {
+    test_spread_angles();
+    test_spread_shot_fans_out_from_the_muzzle();
+    test_muzzle_turns_with_the_ship();
+    test_loadout_cooldown_and_switching();
+    test_switch_key_cycles_weapons();
+    test_every_weapon_fires_its_projectile();
+    test_rail_shot_pierces_asteroids();
+    test_laser_burns_the_first_asteroid_in_line();
+    test_laser_drains_and_recharges_energy();
+    test_laser_follows_the_simulated_ship();
+    test_acquire_target_picks_the_nearest_in_the_cone();
+    test_acquire_target_wraps_across_the_seam();
+    test_steer_limits_the_turn();
+    test_missile_homes_in_and_leaves_a_trail();
+    test_missile_blast_damages_everything_in_reach();
+}
+
+/// Tests that shots are spaced evenly across the arc and centered on the nose
+fn test_spread_angles() {
+    assert_eq!(Spread::SINGLE.angles(), vec![0.0]);
+    assert!(Spread { shots: 0, arc: 1.0 }.angles().is_empty());
+
+    let angles = Spread { shots: 3, arc: 1.0 }.angles();
+    assert_eq!(angles.len(), 3);
+    assert!((angles[0] + 0.5).abs() < 1e-6);
+    assert!(angles[1].abs() < 1e-6);
+    assert!((angles[2] - 0.5).abs() < 1e-6);
+}
+
+/// Tests that a spread shot fires every round from the muzzle at the projectile's speed
+fn test_spread_shot_fans_out_from_the_muzzle() {
+    let origin = Vec2::new(100.0, -50.0);
+    let shots = SpreadShot.fire(origin, Quat::IDENTITY);
+    assert_eq!(shots.len() as u32, SpreadShot.spread().shots);
+
+    let speed = SpreadShot.projectile().unwrap().speed;
+    for shot in &shots {
+        assert_eq!(shot.position, origin + SpreadShot.muzzle());
+        assert!((shot.velocity.length() - speed).abs() < 1e-3);
+        // Every round heads forward, none off to the side
+        assert!(shot.velocity.y > 0.0);
+    }
+    // Fanned symmetrically around the nose
+    let sideways: f32 = shots.iter().map(|shot| shot.velocity.x).sum();
+    assert!(sideways.abs() < 1e-3);
+    assert!(shots.first().unwrap().velocity.x > 0.0);
+}
+
+/// Tests that the muzzle and the shot turn along with the ship
+fn test_muzzle_turns_with_the_ship() {
+    // A quarter turn to the left points the nose along -X
+    let rotation = Quat::from_rotation_z(std::f32::consts::FRAC_PI_2);
+    let shots = SingleShot.fire(Vec2::ZERO, rotation);
+    assert_eq!(shots.len(), 1);
+    assert!((shots[0].position - Vec2::new(-MUZZLE_DISTANCE, 0.0)).length() < 1e-3);
+    let speed = Projectile::STANDARD.speed;
+    assert!((shots[0].velocity - Vec2::new(-speed, 0.0)).length() < 1e-2);
+}
+
+/// Tests that the cooldown holds the trigger back and switching cycles through every weapon
+fn test_loadout_cooldown_and_switching() {
+    let mut loadout = Loadout::default();
+    assert_eq!(loadout.len(), 6);
+    assert_eq!(loadout.current().name(), SingleShot.name());
+
+    // A fresh ship waits out one interval before its first shot
+    assert!(loadout.trigger(Vec2::ZERO, Quat::IDENTITY).is_none());
+    loadout.tick(Duration::from_secs_f32(SingleShot.fire_interval()));
+    assert_eq!(loadout.trigger(Vec2::ZERO, Quat::IDENTITY).map(|shots| shots.len()), Some(1));
+    assert!(loadout.trigger(Vec2::ZERO, Quat::IDENTITY).is_none());
+
+    let names: Vec<&str> = (0..loadout.len())
+        .map(|_| {
+            loadout.switch_next();
+            loadout.current().name()
+        })
+        .collect();
+    assert_eq!(names, ["Spread", "Cannon", "Rail", "Laser", "Missile", "Single"]);
+    assert_eq!(loadout.selected(), 0);
+
+    // Switching to a faster gun does not cut the wait after a slow one
+    let mut loadout = Loadout::new(vec![Box::new(RailShot), Box::new(RapidCannon)]);
+    loadout.tick(Duration::from_secs_f32(RailShot.fire_interval()));
+    assert!(loadout.trigger(Vec2::ZERO, Quat::IDENTITY).is_some());
+    loadout.switch_next();
+    loadout.tick(Duration::from_secs_f32(RapidCannon.fire_interval()));
+    assert!(loadout.trigger(Vec2::ZERO, Quat::IDENTITY).is_none());
+    assert!(RapidCannon.fire_interval() < SingleShot.fire_interval());
+
+    // A beam in hand never pulls the trigger, it fires no projectiles at all
+    let mut loadout = Loadout::new(vec![Box::new(LaserBeam)]);
+    assert!(LaserBeam.projectile().is_none());
+    assert!(LaserBeam.fire(Vec2::ZERO, Quat::IDENTITY).is_empty());
+    for _ in 0..3 {
+        loadout.tick(Duration::from_secs_f32(0.1));
+        assert!(loadout.trigger(Vec2::ZERO, Quat::IDENTITY).is_none());
+    }
+}
+
+/// The weapon in hand on player one's ship
+fn weapon_name(game: &mut HeadlessGame) -> &'static str {
+    let world = game.world_mut();
+    world
+        .query_filtered::<&Loadout, With<Player>>()
+        .single(world)
+        .current()
+        .name()
+}
+
+/// Tests that the switch weapon key takes up the next weapon once per press
+fn test_switch_key_cycles_weapons() {
+    let mut game = HeadlessGame::new(11);
+    game.start_game();
+    game.step(1);
+    assert_eq!(weapon_name(&mut game), "Single");
+
+    game.press(KeyCode::KeyE);
+    game.step(3);
+    assert_eq!(weapon_name(&mut game), "Spread");
+    game.release(KeyCode::KeyE);
+    game.step(1);
+    game.press(KeyCode::KeyE);
+    game.step(1);
+    assert_eq!(weapon_name(&mut game), "Cannon");
+}
+
+/// Tests that holding fire with each gun spawns its own projectiles
+fn test_every_weapon_fires_its_projectile() {
+    for switches in 0..4 {
+        let mut game = HeadlessGame::new(12);
+        game.start_game();
+        // Let the fresh ship's first cooldown run out, so only the weapon in hand's counts
+        let ticks = (SingleShot.fire_interval() / game.tick_duration().as_secs_f32()).ceil() as u32;
+        game.step(ticks + 1);
+        for _ in 0..switches {
+            game.press(KeyCode::KeyE);
+            game.step(1);
+            game.release(KeyCode::KeyE);
+            game.step(1);
+        }
+
+        let world = game.world_mut();
+        let loadout = world.query::<&Loadout>().single(world);
+        let interval = loadout.current().fire_interval();
+        let expected = loadout.current().spread().shots as usize;
+        let projectile = loadout.current().projectile().unwrap();
+
+        game.press(KeyCode::Space);
+        let ticks = (interval / game.tick_duration().as_secs_f32()).ceil() as u32 + 1;
+        game.step(ticks);
+
+        let world = game.world_mut();
+        let bullets: Vec<(&Bullet, &BoundingRadius)> =
+            world.query::<(&Bullet, &BoundingRadius)>().iter(world).collect();
+        assert!(bullets.len() >= expected, "weapon {switches}");
+        for (bullet, radius) in bullets {
+            assert_eq!(radius.0, projectile.radius);
+            assert_eq!(bullet.lifetime.duration().as_secs_f32(), projectile.lifetime);
+        }
+    }
+}
+
+/// Tests that a rail slug damages each asteroid in its path once and stops after its pierce runs out
+fn test_rail_shot_pierces_asteroids() {
+    let mut game = HeadlessGame::new(13);
+    game.start_game();
+    game.step_until_first_wave();
+
+    // Metal holds together, so every hit shows up as a lost hit point
+    let rocks = game.recompose_asteroids(Composition::Metal);
+    let world = game.world_mut();
+    let projectile = Projectile { pierce: 1, ..RailShot.projectile().unwrap() };
+    let slug = world
+        .spawn((
+            Bullet::new(&projectile),
+            Faction::Player(0),
+            Position(Vec2::ZERO),
+            BoundingRadius(projectile.radius),
+            LinearVelocity(Vec2::new(0.0, projectile.speed)),
+        ))
+        .id();
+    let full = Asteroid::new(AsteroidSize::Large, Composition::Metal).hit_points;
+
+    // Through the first rock, touching it again on the way out
+    world.send_event(CollisionStarted(slug, rocks[0]));
+    game.step(1);
+    game.world_mut().send_event(CollisionStarted(slug, rocks[0]));
+    game.step(1);
+    assert_eq!(game.world().get::<Asteroid>(rocks[0]).unwrap().hit_points, full - 1);
+    assert!(game.world().get_entity(slug).is_some());
+
+    // Spent on the second
+    game.world_mut().send_event(CollisionStarted(slug, rocks[1]));
+    game.step(1);
+    assert_eq!(game.world().get::<Asteroid>(rocks[1]).unwrap().hit_points, full - 1);
+    assert!(game.world().get_entity(slug).is_none());
+}
+
+/// Starts a game and switches player one's ship to the laser, well before the first wave
+fn game_with_laser(seed: u64) -> HeadlessGame {
+    let mut game = HeadlessGame::new(seed);
+    game.start_game();
+    game.step(1);
+    while weapon_name(&mut game) != LaserBeam.name() {
+        game.press(KeyCode::KeyE);
+        game.step(1);
+        game.release(KeyCode::KeyE);
+        game.step(1);
+    }
+    game
+}
+
+/// Parks a rock of the given size at `position`, with everything the beam needs to hit it
+fn park_rock(world: &mut World, size: AsteroidSize, position: Vec2) -> Entity {
+    world
+        .spawn((
+            Asteroid::new(size, Composition::Rock),
+            Position(position),
+            Collider::circle(size.radius()),
+            BoundingRadius(size.radius()),
+            LinearVelocity(Vec2::ZERO),
+            AngularVelocity(0.0),
+            ColliderDensity(1.0),
+        ))
+        .id()
+}
+
+/// Tests that the beam burns down a small rock ahead of the ship while the rock behind it stays untouched
+fn test_laser_burns_the_first_asteroid_in_line() {
+    let mut game = game_with_laser(14);
+    let world = game.world_mut();
+    let ship = *world.query_filtered::<&Transform, With<Player>>().single(world);
+    let forward = (ship.rotation * Vec3::Y).truncate();
+    let origin = ship.translation.truncate();
+    let near = park_rock(world, AsteroidSize::Small, origin + forward * 150.0);
+    let far = park_rock(world, AsteroidSize::Large, origin + forward * 300.0);
+
+    let beam = LaserBeam.beam().unwrap();
+    game.press(KeyCode::Space);
+    game.step(2);
+    // The beam runs from the muzzle to where it meets the near rock
+    let world = game.world_mut();
+    let length = world.query_filtered::<&Transform, With<BeamVisual>>().single(world).scale.y;
+    let to_center = 150.0 - LaserBeam.muzzle().y;
+    assert!(length < to_center && length > to_center - AsteroidSize::Small.radius() * 2.0);
+    assert!(world.query::<&Bullet>().iter(world).next().is_none());
+
+    // One hit point burns down in a second over the beam's damage rate
+    let seconds = 1.0 / beam.damage_per_second;
+    let ticks = (seconds / game.tick_duration().as_secs_f32()).ceil() as u32 + 1;
+    let mut burned = 2;
+    while game.world().get_entity(near).is_some() {
+        assert!(burned <= ticks, "the beam should have burned the rock down");
+        game.step(1);
+        burned += 1;
+    }
+    assert!(burned >= ticks - 2);
+    let points = Asteroid::new(AsteroidSize::Small, Composition::Rock).points();
+    assert_eq!(game.world().resource::<Score>().points[0], points);
+
+    // The near rock shielded the far one the whole time
+    assert_eq!(game.world().get::<Asteroid>(far).unwrap().beam_damage, 0.0);
+}
+
+/// Tests that the beam cuts out once the pool runs dry and only restarts once part of it is back
+fn test_laser_drains_and_recharges_energy() {
+    let mut game = game_with_laser(15);
+    let beam = LaserBeam.beam().unwrap();
+    let tick = game.tick_duration().as_secs_f32();
+    let beam_count = |game: &mut HeadlessGame| {
+        let world = game.world_mut();
+        world.query::<&BeamVisual>().iter(world).count()
+    };
+    let energy = |game: &mut HeadlessGame| {
+        let world = game.world_mut();
+        *world.query::<&Energy>().single(world)
+    };
+
+    game.press(KeyCode::Space);
+    let ticks = (ENERGY_CAPACITY / beam.energy_per_second / tick).ceil() as u32 + 2;
+    game.step(ticks);
+    assert!(energy(&mut game).depleted);
+    assert_eq!(beam_count(&mut game), 0);
+
+    // Still held, the beam waits for the pool to come back to the restart level
+    let restart = ENERGY_CAPACITY * ENERGY_RESTART_FRACTION / ENERGY_RECHARGE;
+    let ticks = (restart / tick).floor() as u32 - 4;
+    game.step(ticks);
+    assert_eq!(beam_count(&mut game), 0);
+    game.step(6);
+    assert!(!energy(&mut game).depleted);
+    assert_eq!(beam_count(&mut game), 1);
+
+    game.release(KeyCode::Space);
+    game.step(1);
+    let before = energy(&mut game).current;
+    let ticks = (1.0 / tick).round() as u32;
+    game.step(ticks);
+    let recharged = energy(&mut game).current - before;
+    assert!((recharged - ENERGY_RECHARGE * ticks as f32 * tick).abs() < 0.1);
+}
+
+/// Tests that the beam points where the simulated ship does, not where it is drawn between ticks
+fn test_laser_follows_the_simulated_ship() {
+    let mut game = game_with_laser(16);
+    game.step_with_overstep(0.5);
+    game.press(KeyCode::KeyA);
+    game.press(KeyCode::Space);
+    game.step(5);
+
+    let world = game.world_mut();
+    let (rendered, heading) = world
+        .query_filtered::<(&Transform, &PhysicalRotation), With<Player>>()
+        .single(world);
+    let simulated = Quat::from_rotation_z(heading.0);
+    // The drawn ship trails the simulation by half a tick of turning
+    assert!(!rendered.rotation.abs_diff_eq(simulated, 1e-4));
+    let beam = world.query_filtered::<&Transform, With<BeamVisual>>().single(world);
+    assert!(beam.rotation.abs_diff_eq(simulated, 1e-4));
+}
+
+/// The launcher's homing, with a cone of about 34 degrees to either side
+fn homing() -> Homing {
+    MissileLauncher.projectile().unwrap().homing.unwrap()
+}
+
+/// Tests that the nearest target inside the cone and in range is picked
+fn test_acquire_target_picks_the_nearest_in_the_cone() {
+    let bounds = WorldBounds::new(800.0, 600.0);
+    let homing = homing();
+    let [ahead, nearer_aside, behind, far] = [1, 2, 3, 4].map(Entity::from_raw);
+    let candidates = [
+        (ahead, Vec2::new(20.0, 150.0)),
+        // Closer, but well outside the cone
+        (nearer_aside, Vec2::new(100.0, 0.0)),
+        (behind, Vec2::new(0.0, -50.0)),
+        (far, Vec2::new(0.0, homing.range + 1.0)),
+    ];
+    let picked = acquire_target(&bounds, Vec2::ZERO, Vec2::Y, &homing, candidates);
+    assert_eq!(picked, Some(ahead));
+
+    // Nothing in the cone leaves the missile flying straight
+    let aside = candidates[..2].to_vec();
+    let picked = acquire_target(&bounds, Vec2::ZERO, Vec2::NEG_Y, &homing, aside);
+    assert_eq!(picked, None);
+
+    // Equally close targets go to the lower entity either way round
+    let twins = [(far, Vec2::new(0.0, 100.0)), (ahead, Vec2::new(0.0, 100.0))];
+    assert_eq!(acquire_target(&bounds, Vec2::ZERO, Vec2::Y, &homing, twins), Some(ahead));
+}
+
+/// Tests that a target just across the seam ahead beats a farther one on this side
+fn test_acquire_target_wraps_across_the_seam() {
+    let bounds = WorldBounds::new(800.0, 600.0);
+    let extents = bounds.wrap_extents();
+    let homing = homing();
+    let [across, inside] = [1, 2].map(Entity::from_raw);
+
+    // Near the right edge heading right, the rock at the far left is just ahead
+    let position = Vec2::new(extents.x - 20.0, 0.0);
+    let candidates = [
+        (across, Vec2::new(-extents.x + 40.0, 0.0)),
+        (inside, Vec2::new(extents.x - 220.0, 0.0)),
+    ];
+    assert_eq!(acquire_target(&bounds, position, Vec2::X, &homing, candidates), Some(across));
+    // Heading left, the same rock is behind and the other one is picked
+    assert_eq!(acquire_target(&bounds, position, Vec2::NEG_X, &homing, candidates), Some(inside));
+}
+
+/// Tests that steering turns no further than allowed and keeps the speed
+fn test_steer_limits_the_turn() {
+    let velocity = Vec2::new(0.0, 200.0);
+    let turned = steer(velocity, Vec2::X, 0.1);
+    assert!((turned.length() - 200.0).abs() < 1e-3);
+    assert!((velocity.angle_between(turned) + 0.1).abs() < 1e-5);
+
+    // A small correction is made in full
+    let desired = Vec2::from_angle(0.05).rotate(velocity);
+    assert!(steer(velocity, desired, 0.1).angle_between(desired).abs() < 1e-5);
+    assert_eq!(steer(velocity, Vec2::ZERO, 0.1), velocity);
+}
+
+/// Spawns a player one missile at `position` flying at `velocity`
+fn launch_missile(world: &mut World, position: Vec2, velocity: Vec2) -> Entity {
+    let projectile = MissileLauncher.projectile().unwrap();
+    world
+        .spawn((
+            Bullet::new(&projectile),
+            Missile::new(projectile.homing.unwrap()),
+            Missile::facing(velocity),
+            Faction::Player(0),
+            Position(position),
+            BoundingRadius(projectile.radius),
+            LinearVelocity(velocity),
+        ))
+        .id()
+}
+
+/// Tests that a missile locks onto a rock off its nose, turns towards it and trails exhaust
+fn test_missile_homes_in_and_leaves_a_trail() {
+    let mut game = HeadlessGame::new(16);
+    game.start_game();
+    game.step(1);
+    let world = game.world_mut();
+    let particles = world.query::<&Particle>().iter(world).count();
+    let speed = MissileLauncher.projectile().unwrap().speed;
+    let start = Vec2::new(-200.0, -150.0);
+    let missile = launch_missile(world, start, Vec2::new(0.0, speed));
+    let rock = park_rock(world, AsteroidSize::Large, start + Vec2::new(100.0, 200.0));
+
+    let ticks = 5;
+    game.step(ticks);
+    let world = game.world_mut();
+    assert_eq!(world.get::<Missile>(missile).unwrap().target, Some(rock));
+    let velocity = world.get::<LinearVelocity>(missile).unwrap().0;
+    assert!((velocity.length() - speed).abs() < 1e-2);
+    // Turned right towards the rock, by no more than its turn rate allows
+    let turned = Vec2::Y.angle_between(velocity);
+    let most = homing().turn_rate * game.tick_duration().as_secs_f32() * ticks as f32;
+    assert!(turned < 0.0 && -turned <= most + 1e-4, "turned {turned}");
+
+    let world = game.world_mut();
+    assert!(world.query::<&Particle>().iter(world).count() > particles);
+}
+
+/// Tests that a blast breaks every rock and saucer within reach and leaves the rest alone
+fn test_missile_blast_damages_everything_in_reach() {
+    let mut game = HeadlessGame::new(17);
+    game.start_game();
+    game.step(1);
+    let world = game.world_mut();
+    let blast = homing().blast_radius;
+    let center = Vec2::new(-200.0, 150.0);
+    let missile = launch_missile(world, center, Vec2::new(0.0, 100.0));
+    let struck = park_rock(world, AsteroidSize::Small, center + Vec2::new(0.0, 10.0));
+    let caught = park_rock(world, AsteroidSize::Small, center + Vec2::new(blast, 0.0));
+    let clear = park_rock(world, AsteroidSize::Small, center + Vec2::new(-blast * 2.0, 0.0));
+    let size = SaucerSize::Small;
+    let saucer = world
+        .spawn((
+            Saucer {
+                size,
+                direction: 1.0,
+                elapsed: 0.0,
+                fire_timer: Timer::from_seconds(100.0, TimerMode::Once),
+            },
+            Transform::from_translation((center - Vec2::new(0.0, blast)).extend(0.0)),
+            LinearVelocity(Vec2::ZERO),
+        ))
+        .id();
+
+    world.send_event(CollisionStarted(missile, struck));
+    game.step(1);
+    let world = game.world();
+    assert!(world.get_entity(missile).is_none());
+    assert!(world.get_entity(struck).is_none());
+    assert!(world.get_entity(caught).is_none());
+    assert!(world.get_entity(clear).is_some());
+    assert!(world.get_entity(saucer).is_none());
+    let rock = Asteroid::new(AsteroidSize::Small, Composition::Rock).points();
+    assert_eq!(world.resource::<Score>().points[0], rock * 2 + size.points());
+}